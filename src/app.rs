@@ -1,104 +1,503 @@
+use chrono::{Local, NaiveDate};
 use rand::Rng;
 use std::io;
+use std::iter;
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
 use std::time::{Duration, Instant};
 
 use ratatui::widgets::Paragraph;
 use ratatui::{
     DefaultTerminal, Frame,
     buffer::Buffer,
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Flex, Layout, Margin, Rect},
     style::Stylize,
     symbols::border,
-    text::Line,
-    widgets::{Block, Widget},
+    text::{Line, Span, Text},
+    widgets::{Block, Row, Table, Widget, Wrap},
 };
 
-use crate::components::dice::Dice;
-use crate::score_util::calc_score;
+use crate::bot::{Bot, Decision, Difficulty, external::ExternalBot};
+use crate::components::{
+    dice::Dice, high_score_table::HighScoreTable, hint_panel::HintPanel, menu::Menu,
+    standings::{Standings, short_label}, stats_panel::StatsPanel,
+};
+use crate::daily::{self, DailyResults};
+use crate::game::{Event, Game, TurnState, mode::Mode, roller::{DiceRoller, SeededRoller}};
+use crate::high_scores::{HighScore, HighScores, Variant};
+use crate::hint::{Hint, HintKey};
+use crate::keymap::{Action, Keymap};
+use crate::model::die::DieFace;
+use crate::model::house_rules::HouseRules;
+use crate::model::player::{self, MAX_PLAYERS, Player};
+use crate::model::roll::RollType;
+use crate::model::ruleset::Ruleset;
+use crate::net::{self, Command, Guest, Host, HostMessage, Session};
+use crate::replay::{self, Playback, Replay};
+use crate::save::{self, SaveData};
+use crate::settings::{BotSpeed, Settings};
+use crate::solver::Solver;
+use crate::stats::{GameRecord, Stats};
 use crate::theme::Theme;
-use crate::{components::roll_slots::RollSlots, event, model::roll::AllRolls};
+use crate::{components::roll_slots::RollSlots, event};
 
-const HEIGHT: u8 = 18;
-const WIDTH: u8 = 58;
+/// Size of the board besides the scorecard slots and the dice.
+const HEIGHT: u8 = 9;
+const WIDTH: u8 = 13;
+const MAX_NAME_LEN: usize = 16;
+const MAX_LOG_ENTRIES: usize = 100;
 
-pub struct App {
-    pub exit: bool,
-    pub rolls: AllRolls,
-    pub dice_faces: Vec<DieFace>,
-    pub roll_count: u8,
-}
+/// Everything the title menu offers. Resume is left out while there is no
+/// game to go back to.
+pub const TITLE_MENU: [&str; 8] = [
+    "New Game",
+    "Resume",
+    "Daily",
+    "High Scores",
+    "Statistics",
+    "Settings",
+    "Help",
+    "Quit",
+];
+/// Rows of the new game screen above the players.
+const SETUP_OPTIONS: usize = 2;
+const SETTINGS_MENU: [&str; 3] = ["One undo per turn", "Hints", "Bot speed"];
 
-#[derive(Clone, Copy)]
-pub struct DieFace {
-    pub value: u8,
-    pub held: bool,
-    pub rolling_until: Option<Instant>,
+#[derive(Clone, Copy, PartialEq)]
+pub enum Screen {
+    Title,
+    Setup,
+    Playing,
+    Standings,
+    NameEntry,
+    HighScores,
+    Statistics,
+    Settings,
+    Help,
+    Log,
+    Replay,
 }
 
-impl DieFace {
-    pub fn new(value: u8) -> Self {
-        Self {
-            value,
-            held: false,
-            rolling_until: None,
-        }
-    }
-
-    pub fn is_rolling(self) -> bool {
-        self.rolling_until.is_some()
-    }
+pub struct App {
+    pub exit: bool,
+    pub screen: Screen,
+    pub menu_index: usize,
+    /// Players for the next game, in turn order.
+    pub lineup: Vec<Player>,
+    /// Mode and ruleset of the next game, and of the high score table shown.
+    pub mode: Mode,
+    pub ruleset: Ruleset,
+    pub house_rules: HouseRules,
+    /// Row selected on the new game screen: the mode, the ruleset, then
+    /// the players.
+    pub setup_index: usize,
+    pub game: Game,
+    pub roller: Box<dyn DiceRoller>,
+    /// The dice as displayed, which show random faces while rolling.
+    pub dice_faces: Vec<DieFace>,
+    /// A saved game waiting for the player to resume or discard it.
+    pub saved_game: Option<SaveData>,
+    pub high_scores: HighScores,
+    /// Every finished game, for the statistics screen.
+    pub stats: Stats,
+    pub name_input: String,
+    /// Position of the score just added to the high score table.
+    pub new_high_score: Option<usize>,
+    pub show_hint: bool,
+    /// The hint for the game as it stands, worked out again only once it
+    /// moves on.
+    hint: Option<(HintKey, Hint)>,
+    pub solver: Option<Arc<Solver>>,
+    /// Receives the strategy table while it loads in the background.
+    solver_loading: Option<Receiver<Solver>>,
+    /// Computer players of the current game by seat, created on their first turn.
+    bots: Vec<Option<Bot>>,
+    /// External programs of the current game by seat, started on their first turn.
+    programs: Vec<Option<ExternalBot>>,
+    /// What the current bot decided, carried out after a pause.
+    bot_plan: Option<Decision>,
+    bot_wait_until: Option<Instant>,
+    /// What happened so far this game, oldest first.
+    pub log: Vec<String>,
+    pub settings: Settings,
+    pub settings_index: usize,
+    pub keymap: Keymap,
+    /// The seed of the dice roller, recorded with the first game's replay.
+    pub seed: Option<u64>,
+    /// The replay being watched instead of playing.
+    pub playback: Option<Playback>,
+    /// The day whose challenge is being played.
+    pub daily: Option<NaiveDate>,
+    pub daily_results: DailyResults,
+    /// Whether the daily game is the day's first try, the only one recorded.
+    /// Later ones are practice.
+    pub daily_counts: bool,
+    /// The connection of a game played over the network.
+    pub net: Option<Session>,
 }
 
 impl App {
-    pub fn new() -> App {
+    pub fn new(roller: Box<dyn DiceRoller>) -> App {
+        let game = Game::new();
         App {
             exit: false,
-            rolls: AllRolls::new(),
-            roll_count: 0,
-            dice_faces: vec![
-                DieFace::new(1),
-                DieFace::new(2),
-                DieFace::new(3),
-                DieFace::new(4),
-                DieFace::new(5),
-            ],
+            screen: Screen::Title,
+            menu_index: 0,
+            lineup: vec![Player::new("Player 1")],
+            mode: Mode::Standard,
+            ruleset: Ruleset::Standard,
+            house_rules: HouseRules::default(),
+            setup_index: 0,
+            roller,
+            dice_faces: game.dice().to_vec(),
+            game,
+            saved_game: None,
+            high_scores: HighScores::new(),
+            stats: Stats::new(),
+            name_input: String::new(),
+            new_high_score: None,
+            show_hint: false,
+            hint: None,
+            solver: None,
+            solver_loading: None,
+            bots: Vec::new(),
+            programs: Vec::new(),
+            bot_plan: None,
+            bot_wait_until: None,
+            log: Vec::new(),
+            settings: Settings::default(),
+            settings_index: 0,
+            keymap: Keymap::default(),
+            seed: None,
+            playback: None,
+            daily: None,
+            daily_results: DailyResults::new(),
+            daily_counts: false,
+            net: None,
         }
     }
 
     pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        // games started from the command line skip the menu
+        if self.screen != Screen::Title && self.show_hint && self.has_hints() {
+            self.load_solver();
+        }
         while !self.exit {
+            self.update_hint();
             terminal.draw(|frame| draw(self, frame))?;
             event::handle_events(self)?;
         }
-        Ok(())
+        self.save_game()
+    }
+
+    /// Saves an unfinished game so it can be resumed next time, or removes
+    /// the old save once there is nothing left to resume.
+    fn save_game(&self) -> io::Result<()> {
+        if self.saved_game.is_some() || self.playback.is_some() || self.net.is_some() {
+            // the player never went back to the saved game, only watched a
+            // replay or played a game the other end has a say in
+            return Ok(());
+        }
+        // a daily challenge would not roll the day's dice once resumed
+        if self.game.has_started() && !self.game.is_game_over() && self.daily.is_none() {
+            save::store(&SaveData::from_game(&self.game))
+        } else {
+            save::clear()
+        }
+    }
+
+    fn resume_saved_game(&mut self) {
+        if let Some(save) = self.saved_game.take() {
+            self.leave_daily();
+            self.game = save
+                .into_game()
+                .with_one_undo_per_turn(self.settings.one_undo_per_turn);
+            self.dice_faces = self.game.dice().to_vec();
+            self.mode = self.game.mode();
+            self.ruleset = self.game.ruleset();
+            self.house_rules = self.game.house_rules();
+            self.seed = None;
+            self.reset_bots();
+        }
+    }
+
+    /// Whether there is a game to go back to, either the one left for the
+    /// menu or the one saved last time.
+    pub fn can_resume(&self) -> bool {
+        self.is_game_in_progress() || self.saved_game.is_some()
+    }
+
+    fn is_game_in_progress(&self) -> bool {
+        self.game.has_started() && !self.is_game_over()
+    }
+
+    /// Goes back to the game left for the menu, or else the saved one.
+    pub fn resume(&mut self) {
+        if !self.is_game_in_progress() {
+            self.resume_saved_game();
+        }
+        self.show_board();
     }
 
     pub fn exit(&mut self) {
         self.exit = true;
     }
 
-    pub fn start_roll(&mut self) {
-        if self.roll_count < 3 {
-            self.rolls.clear_selection();
-            self.roll_count += 1;
-            for die in &mut self.dice_faces {
-                if !die.held {
-                    let duration = rand::rng().random_range(500..=1000);
-                    die.rolling_until = Some(Instant::now() + Duration::from_millis(duration));
+    /// The title menu items currently offered.
+    pub fn title_menu(&self) -> Vec<&'static str> {
+        TITLE_MENU
+            .into_iter()
+            .filter(|item| *item != "Resume" || self.can_resume())
+            .collect()
+    }
+
+    pub fn select_next_menu_item(&mut self) {
+        let len = self.title_menu().len();
+        self.menu_index = (self.menu_index + 1) % len;
+    }
+
+    pub fn select_prev_menu_item(&mut self) {
+        let len = self.title_menu().len();
+        self.menu_index = (self.menu_index + len - 1) % len;
+    }
+
+    pub fn choose_menu_item(&mut self) {
+        match self.title_menu()[self.menu_index] {
+            "New Game" => {
+                self.setup_index = 0;
+                self.screen = Screen::Setup;
+            }
+            "Resume" => self.resume(),
+            "Daily" => self.start_daily(),
+            "High Scores" => self.show_high_scores(None),
+            "Statistics" => self.screen = Screen::Statistics,
+            "Settings" => {
+                self.settings_index = 0;
+                self.screen = Screen::Settings;
+            }
+            "Help" => self.screen = Screen::Help,
+            _ => self.exit(),
+        }
+    }
+
+    /// Shows the title menu, where Resume comes and goes with the game.
+    pub fn show_title(&mut self) {
+        self.menu_index = self.menu_index.min(self.title_menu().len() - 1);
+        self.screen = Screen::Title;
+    }
+
+    /// Shows the dice, loading the strategy table if the hint is to be shown.
+    fn show_board(&mut self) {
+        if self.show_hint && self.has_hints() {
+            self.load_solver();
+        }
+        self.screen = Screen::Playing;
+    }
+
+    pub fn toggle_log(&mut self) {
+        self.screen = match self.screen {
+            Screen::Log => self.board_screen(),
+            _ => Screen::Log,
+        };
+    }
+
+    pub fn toggle_standings(&mut self) {
+        self.screen = match self.screen {
+            Screen::Standings => self.board_screen(),
+            _ => Screen::Standings,
+        };
+    }
+
+    /// The screen showing the dice, which the log and table return to.
+    fn board_screen(&self) -> Screen {
+        if self.playback.is_some() {
+            Screen::Replay
+        } else {
+            Screen::Playing
+        }
+    }
+
+    pub fn select_next_setup_row(&mut self) {
+        self.setup_index = (self.setup_index + 1) % (SETUP_OPTIONS + self.lineup.len());
+    }
+
+    pub fn select_prev_setup_row(&mut self) {
+        let len = SETUP_OPTIONS + self.lineup.len();
+        self.setup_index = (self.setup_index + len - 1) % len;
+    }
+
+    /// The player selected on the new game screen, if any.
+    fn setup_player(&self) -> Option<usize> {
+        self.setup_index.checked_sub(SETUP_OPTIONS)
+    }
+
+    /// Whether a player is selected on the new game screen, so typing
+    /// edits its name.
+    pub fn is_naming_player(&self) -> bool {
+        self.setup_player().is_some()
+    }
+
+    pub fn add_player(&mut self) {
+        if self.lineup.len() < MAX_PLAYERS {
+            self.lineup.push(Player::new(format!("Player {}", self.lineup.len() + 1)));
+            self.setup_index = SETUP_OPTIONS + self.lineup.len() - 1;
+        }
+    }
+
+    pub fn remove_player(&mut self) {
+        if let Some(index) = self.setup_player()
+            && self.lineup.len() > 1
+        {
+            self.lineup.remove(index);
+            self.setup_index = self.setup_index.min(SETUP_OPTIONS + self.lineup.len() - 1);
+        }
+    }
+
+    pub fn push_player_name_char(&mut self, c: char) {
+        if let Some(index) = self.setup_player() {
+            let name = &mut self.lineup[index].name;
+            if name.chars().count() < MAX_NAME_LEN {
+                name.push(c);
+            }
+        }
+    }
+
+    pub fn pop_player_name_char(&mut self) {
+        if let Some(index) = self.setup_player() {
+            self.lineup[index].name.pop();
+        }
+    }
+
+    /// Changes the selected row of the new game screen: the mode, the
+    /// ruleset or the kind of player.
+    pub fn cycle_setup_option(&mut self, forward: bool) {
+        match self.setup_index {
+            0 => self.mode = cycle(&Mode::ALL, self.mode, forward),
+            1 => self.ruleset = cycle(&Ruleset::ALL, self.ruleset, forward),
+            _ => self.cycle_player_kind(forward),
+        }
+    }
+
+    /// Switches the selected player between a human and each bot difficulty.
+    /// An external program is replaced by the next kind.
+    fn cycle_player_kind(&mut self, forward: bool) {
+        let Some(index) = self.setup_player() else {
+            return;
+        };
+        let kinds: Vec<Option<Difficulty>> =
+            iter::once(None).chain(Difficulty::ALL.map(Some)).collect();
+        let player = &mut self.lineup[index];
+        player.bot = cycle(&kinds, player.bot, forward);
+        player.command = None;
+    }
+
+    /// Starts a game as set up on the new game screen, in place of any
+    /// saved or unfinished one.
+    pub fn start_new_game(&mut self) {
+        self.saved_game = None;
+        self.leave_daily();
+        self.start_over();
+        self.show_board();
+    }
+
+    pub fn select_next_setting(&mut self) {
+        self.settings_index = (self.settings_index + 1) % SETTINGS_MENU.len();
+    }
+
+    pub fn select_prev_setting(&mut self) {
+        self.settings_index = (self.settings_index + SETTINGS_MENU.len() - 1) % SETTINGS_MENU.len();
+    }
+
+    /// Changes the selected setting and keeps it for next time.
+    pub fn change_setting(&mut self, forward: bool) -> io::Result<()> {
+        match self.settings_index {
+            0 => self.settings.one_undo_per_turn = !self.settings.one_undo_per_turn,
+            1 => {
+                self.settings.hints = !self.settings.hints;
+                self.show_hint = self.settings.hints;
+            }
+            _ => self.settings.bot_speed = cycle(&BotSpeed::ALL, self.settings.bot_speed, forward),
+        }
+        self.settings.store()
+    }
+
+    pub fn is_multiplayer(&self) -> bool {
+        self.game.players().len() > 1
+    }
+
+    pub fn is_triple(&self) -> bool {
+        self.game.mode() == Mode::Triple
+    }
+
+    fn height(&self) -> u16 {
+        u16::from(HEIGHT) + self.slots_height()
+    }
+
+    /// Multiplayer games need an extra row to show whose turn it is, and
+    /// Triple games one for the column totals.
+    fn slots_height(&self) -> u16 {
+        RollSlots::grid_height(self.game.ruleset())
+            + u16::from(self.is_multiplayer())
+            + u16::from(self.is_triple())
+    }
+
+    fn width(&self) -> u16 {
+        u16::from(WIDTH) + Dice::width(self.game.dice().len())
+    }
+
+    pub fn show_high_scores(&mut self, highlight: Option<usize>) {
+        self.new_high_score = highlight;
+        self.screen = Screen::HighScores;
+    }
+
+    pub fn start_roll(&mut self) -> io::Result<()> {
+        if let Some(Session::Guest(guest)) = &mut self.net {
+            guest.send(&Command::Roll);
+            return Ok(());
+        }
+        let first_roll = !self.game.has_started();
+        // the final values are decided here, the animation only shows random faces
+        if self.game.roll(self.roller.as_mut()).is_ok() {
+            self.show_roll();
+            if first_roll && self.daily_counts {
+                // seeing the day's dice uses up its try, even if it is given up
+                if let Some(date) = self.daily {
+                    self.daily_results.start(date);
+                    self.daily_results.store()?;
                 }
             }
-            if self.roll_count == 3 {
-                self.rolls.select_next();
+        }
+        Ok(())
+    }
+
+    /// Tumbles the dice towards a roll that was just made.
+    fn show_roll(&mut self) {
+        self.game.current_player_mut().clear_selection();
+        self.animate_roll(500, 1000);
+        // Ordered games lock the selection to the next slot in order
+        if self.game.state() == TurnState::Choosing || self.game.mode() == Mode::Ordered {
+            self.game.current_player_mut().select_next();
+        }
+    }
+
+    /// Tumbles the unheld dice for a random time between the given millis.
+    fn animate_roll(&mut self, min: u64, max: u64) {
+        let now = Instant::now();
+        for (face, die) in self.dice_faces.iter_mut().zip(self.game.dice()) {
+            if !die.held {
+                let duration = rand::rng().random_range(min..=max);
+                face.rolling_until = Some(now + Duration::from_millis(duration));
             }
         }
     }
 
     pub fn update_dice_animation(&mut self) {
-        for die in &mut self.dice_faces {
-            if die.rolling_until.map_or(false, |i| i > Instant::now()) {
-                die.value = rand::rng().random_range(1..=6);
+        for (face, die) in self.dice_faces.iter_mut().zip(self.game.dice()) {
+            if face.rolling_until.is_some_and(|i| i > Instant::now()) {
+                face.value = rand::rng().random_range(1..=6);
             } else {
-                die.rolling_until = None;
+                *face = *die;
             }
         }
     }
@@ -107,37 +506,769 @@ impl App {
         self.dice_faces.iter().any(|face| face.is_rolling())
     }
 
+    /// Shows or hides the hint, loading the strategy table the first time.
+    pub fn toggle_hint(&mut self) {
+        if !self.has_hints() {
+            return;
+        }
+        self.show_hint = !self.show_hint;
+        if self.show_hint {
+            self.load_solver();
+        }
+    }
+
+    /// The strategy table only covers the standard rules with a free
+    /// choice of category.
+    fn has_hints(&self) -> bool {
+        self.game.rolls().has_strategy_table() && self.game.mode() != Mode::Ordered
+    }
+
+    fn load_solver(&mut self) {
+        if self.solver.is_none() && self.solver_loading.is_none() {
+            let (sender, receiver) = mpsc::channel();
+            thread::spawn(move || {
+                // the app may have exited before the table was ready
+                let _ = sender.send(Solver::load_or_build());
+            });
+            self.solver_loading = Some(receiver);
+        }
+    }
+
+    pub fn is_loading_solver(&self) -> bool {
+        self.solver_loading.is_some()
+    }
+
+    pub fn poll_solver(&mut self) {
+        if let Some(receiver) = &self.solver_loading
+            && let Ok(solver) = receiver.try_recv()
+        {
+            self.solver = Some(Arc::new(solver));
+            self.solver_loading = None;
+        }
+    }
+
+    /// Whether a computer player is due to move.
+    pub fn is_bot_turn(&self) -> bool {
+        self.screen == Screen::Playing
+            && !self.is_game_over()
+            && self.game.current_player().is_bot()
+    }
+
+    /// Takes the current bot's next step: rolling, then showing its holds or
+    /// pick, then carrying them out, pausing in between.
+    pub fn step_bot(&mut self) -> io::Result<()> {
+        if self.is_rolling() || self.bot_wait_until.is_some_and(|t| t > Instant::now()) {
+            return Ok(());
+        }
+        let seat = self.game.current_player_index();
+        let player = self.game.current_player();
+        if let Some(command) = &player.command {
+            if self.programs.get(seat).is_none_or(|program| program.is_none()) {
+                match ExternalBot::spawn(command) {
+                    Ok(program) => {
+                        self.programs.resize_with(self.game.players().len(), || None);
+                        self.programs[seat] = Some(program);
+                    }
+                    Err(err) => return self.forfeit(format!("cannot start: {err}")),
+                }
+            }
+        } else if let Some(difficulty) = player.bot
+            && self.bots.get(seat).is_none_or(|bot| bot.is_none())
+        {
+            if difficulty.needs_solver() && self.solver.is_none() {
+                self.load_solver();
+                return Ok(());
+            }
+            self.bots.resize_with(self.game.players().len(), || None);
+            self.bots[seat] = Some(Bot::new(difficulty, rand::random(), self.solver.clone()).with_mode(self.game.mode()));
+        }
+
+        match self.bot_plan.take() {
+            Some(Decision::Reroll(_)) => self.start_roll()?,
+            Some(Decision::Score(..)) => self.submit_selection()?,
+            None if self.game.state() == TurnState::AwaitingFirstRoll => self.start_roll()?,
+            None => {
+                let dice: Vec<u8> = self.game.dice().iter().map(|d| d.value).collect();
+                let decision = match self.decide(seat) {
+                    Some(Ok(decision)) => decision,
+                    Some(Err(reason)) => return self.forfeit(reason),
+                    // the program is still thinking
+                    None => return Ok(()),
+                };
+                let name = self.game.current_player().name.clone();
+                match &decision {
+                    Decision::Reroll(hold) => {
+                        for (i, held) in hold.iter().enumerate() {
+                            if self.game.dice()[i].held != *held {
+                                self.toggle_hold(i);
+                            }
+                        }
+                        let kept: Vec<String> = dice
+                            .iter()
+                            .zip(hold)
+                            .filter(|(_, held)| **held)
+                            .map(|(value, _)| value.to_string())
+                            .collect();
+                        if kept.is_empty() {
+                            self.log(format!("{name} rerolls everything"));
+                        } else {
+                            self.log(format!("{name} holds {}", kept.join(" ")));
+                        }
+                    }
+                    Decision::Score(column, roll_type) => {
+                        let player = self.game.current_player_mut();
+                        player.clear_selection();
+                        player.columns[*column].roll_mut(*roll_type).selected = true;
+                    }
+                }
+                self.bot_plan = Some(decision);
+            }
+        }
+        self.bot_wait_until = Some(Instant::now() + self.settings.bot_speed.delay());
+        Ok(())
+    }
+
+    /// The move of the bot or program in `seat`, none while a program
+    /// thinks and an error if it forfeits.
+    fn decide(&mut self, seat: usize) -> Option<Result<Decision, String>> {
+        if let Some(program) = self.programs.get_mut(seat).and_then(Option::as_mut) {
+            if !program.is_thinking()
+                && let Err(reason) = program.ask(&self.game)
+            {
+                return Some(Err(reason));
+            }
+            return program.try_answer(&self.game);
+        }
+        let dice: Vec<u8> = self.game.dice().iter().map(|d| d.value).collect();
+        let rolls_left = self.game.rolls_left();
+        let bot = self.bots.get_mut(seat)?.as_mut()?;
+        let decision = bot.decide(&self.game.current_player().columns, &dice, rolls_left);
+        Some(decision.ok_or_else(|| String::from("had nowhere left to score")))
+    }
+
+    /// Puts the current player out of the game for `reason`, stopping the
+    /// program playing for them.
+    fn forfeit(&mut self, reason: String) -> io::Result<()> {
+        let seat = self.game.current_player_index();
+        if let Some(program) = self.programs.get_mut(seat) {
+            *program = None;
+        }
+        let name = self.game.current_player().name.clone();
+        if self.game.forfeit().is_err() {
+            return Ok(());
+        }
+        self.show_forfeit(&name, Some(&reason))
+    }
+
+    fn show_forfeit(&mut self, name: &str, reason: Option<&str>) -> io::Result<()> {
+        match reason {
+            Some(reason) => self.log(format!("{name} forfeits: {reason}")),
+            None => self.log(format!("{name} forfeits")),
+        }
+        self.bot_plan = None;
+        self.dice_faces = self.game.dice().to_vec();
+        self.finish_game_if_over()
+    }
+
+    fn reset_bots(&mut self) {
+        self.bots.clear();
+        self.programs.clear();
+        self.bot_plan = None;
+        self.bot_wait_until = None;
+        self.log.clear();
+    }
+
+    fn log(&mut self, entry: String) {
+        if self.log.len() == MAX_LOG_ENTRIES {
+            self.log.remove(0);
+        }
+        self.log.push(entry);
+    }
+
+    /// Watches `replay` from the start instead of playing.
+    pub fn start_replay(&mut self, replay: Replay) {
+        self.mode = replay.mode;
+        self.ruleset = replay.ruleset;
+        self.house_rules = replay.house_rules;
+        self.playback = Some(Playback::new(replay));
+        self.screen = Screen::Replay;
+        self.show_replay_step(false);
+    }
+
+    /// Hosts a game against the player who joined `host` as `remote_name`,
+    /// under the rules of the next game.
+    pub fn host_game(&mut self, mut host: Host, remote_name: String) -> io::Result<()> {
+        let name = self.lineup.first().map_or("Player 1", |p| p.name.as_str());
+        self.game = Game::with_players(vec![Player::new(name), Player::new(remote_name)])
+            .with_ruleset(self.ruleset)
+            .with_house_rules(self.house_rules)
+            .with_mode(self.mode);
+        host.welcome(&self.game)?;
+        self.start_network_game(Session::Host(host));
+        Ok(())
+    }
+
+    /// Plays a copy of the host's `game`, which moves on only as the host says.
+    pub fn join_game(&mut self, guest: Guest, game: Replay) -> io::Result<()> {
+        let invalid = |err: String| io::Error::new(io::ErrorKind::InvalidData, err);
+        self.game = game.game_at(0).map_err(invalid)?;
+        self.mode = game.mode;
+        self.ruleset = game.ruleset;
+        self.house_rules = game.house_rules;
+        self.start_network_game(Session::Guest(guest));
+        // catch up with the game so far, spectators may join halfway
+        for event in &game.events {
+            let name = self.game.current_player().name.clone();
+            replay::apply(&mut self.game, event).map_err(invalid)?;
+            match event {
+                Event::Score { category, points, column } => self.log_score(&name, *column, *category, *points),
+                Event::Forfeit => self.log(format!("{name} forfeits")),
+                _ => {}
+            }
+        }
+        self.dice_faces = self.game.dice().to_vec();
+        Ok(())
+    }
+
+    fn start_network_game(&mut self, session: Session) {
+        self.dice_faces = self.game.dice().to_vec();
+        self.reset_bots();
+        self.seed = None;
+        self.net = Some(session);
+        self.screen = Screen::Playing;
+    }
+
+    /// The seat played at this terminal in a network game, none for
+    /// spectators.
+    fn local_seat(&self) -> Option<usize> {
+        match &self.net {
+            Some(Session::Host(_)) => Some(0),
+            Some(Session::Guest(guest)) => guest.seat,
+            None => None,
+        }
+    }
+
+    /// Whether the player to move is at the other end of a network game,
+    /// which for spectators is always.
+    pub fn is_remote_turn(&self) -> bool {
+        self.net.is_some()
+            && !self.is_game_over()
+            && self.local_seat() != Some(self.game.current_player_index())
+    }
+
+    pub fn is_spectating(&self) -> bool {
+        self.net.is_some() && self.local_seat().is_none()
+    }
+
+    pub fn is_network_game(&self) -> bool {
+        self.net.is_some()
+    }
+
+    /// Plays what came in over the network: the host checks its clients'
+    /// commands and sends every step on, clients follow the host's steps.
+    pub fn poll_network(&mut self) -> io::Result<()> {
+        match &mut self.net {
+            None => Ok(()),
+            Some(Session::Host(host)) => {
+                for (seat, command) in host.commands() {
+                    let name = self.game.current_player().name.clone();
+                    match net::apply_command(&mut self.game, seat, &command, self.roller.as_mut()) {
+                        Ok(()) => {
+                            let event = self.game.events().and_then(|e| e.last()).cloned();
+                            if let Some(event) = event {
+                                self.show_event(&name, &event)?;
+                            }
+                        }
+                        Err(reason) => {
+                            if let Some(Session::Host(host)) = &mut self.net {
+                                host.reject(seat, reason);
+                            }
+                        }
+                    }
+                }
+                let Some(Session::Host(host)) = &mut self.net else {
+                    return Ok(());
+                };
+                host.broadcast(&self.game);
+                let spectators = host.admit_spectators(&self.game);
+                let departed = host.departed();
+                for name in spectators {
+                    self.log(format!("{name} is watching"));
+                }
+                // the game goes on for the spectators
+                self.take_over_seats(&departed);
+                Ok(())
+            }
+            Some(Session::Guest(guest)) => {
+                let messages = guest.messages();
+                let hung_up = guest.host_hung_up();
+                let seat = guest.seat;
+                for message in messages {
+                    match message {
+                        HostMessage::Event { event } => {
+                            let name = self.game.current_player().name.clone();
+                            if let Err(err) = replay::apply(&mut self.game, &event) {
+                                self.log(format!("Out of step with the host: {err}"));
+                                continue;
+                            }
+                            self.show_event(&name, &event)?;
+                        }
+                        HostMessage::Rejected { reason } => self.log(format!("The host refused: {reason}")),
+                        HostMessage::Welcome { .. } => {}
+                    }
+                }
+                match seat {
+                    Some(seat) if hung_up => {
+                        let others: Vec<usize> = (0..self.game.players().len()).filter(|s| *s != seat).collect();
+                        self.net = None;
+                        self.take_over_seats(&others);
+                    }
+                    // spectators are left with the game as it stood
+                    None if hung_up => self.log(String::from("The host left the game")),
+                    _ => {}
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Shows a step made at the other end of a network game.
+    fn show_event(&mut self, name: &str, event: &Event) -> io::Result<()> {
+        match event {
+            Event::Roll { .. } => self.show_roll(),
+            Event::Hold { die } => self.dice_faces[*die].held = self.game.dice()[*die].held,
+            Event::Score {
+                category,
+                points,
+                column,
+            } => self.show_score(name, *column, *category, *points)?,
+            Event::Undo => self.dice_faces = self.game.dice().to_vec(),
+            Event::Forfeit => self.show_forfeit(name, None)?,
+        }
+        Ok(())
+    }
+
+    /// Hands the seats of players who left a network game to bots, so the
+    /// game can be finished here.
+    fn take_over_seats(&mut self, seats: &[usize]) {
+        for &seat in seats {
+            if self.game.players()[seat].is_bot() {
+                continue;
+            }
+            self.game.hand_to_bot(seat, Difficulty::Heuristic);
+            let name = self.game.players()[seat].name.clone();
+            self.log(format!("{name} left the game, a bot takes over"));
+        }
+    }
+
+    /// Moves the replay on when its next step is due.
+    pub fn tick_replay(&mut self) {
+        if let Some(playback) = &mut self.playback
+            && playback.advance_if_due(Instant::now())
+        {
+            self.show_replay_step(true);
+        }
+    }
+
+    pub fn is_replay_running(&self) -> bool {
+        self.playback.as_ref().is_some_and(|p| !p.paused && !p.is_at_end())
+    }
+
+    pub fn toggle_replay_pause(&mut self) {
+        if let Some(playback) = &mut self.playback {
+            playback.toggle_pause();
+        }
+    }
+
+    pub fn step_replay(&mut self, forward: bool) {
+        if let Some(playback) = &mut self.playback {
+            playback.paused = true;
+            if forward {
+                playback.step_forward();
+            } else {
+                playback.step_back();
+            }
+            self.show_replay_step(forward);
+        }
+    }
+
+    pub fn change_replay_speed(&mut self, faster: bool) {
+        if let Some(playback) = &mut self.playback {
+            if faster {
+                playback.faster();
+            } else {
+                playback.slower();
+            }
+        }
+    }
+
+    /// Rebuilds the game and its log up to the current replay step, rolling
+    /// the dice on screen if the step was a roll.
+    fn show_replay_step(&mut self, animate: bool) {
+        let Some(playback) = &self.playback else {
+            return;
+        };
+        let replay = &playback.replay;
+        let mut game = replay.game_at(0).expect("replays are checked when loaded");
+        let mut log = Vec::new();
+        for event in &replay.events[..playback.step] {
+            let name = match event {
+                Event::Undo => game.last_scored_by(),
+                _ => Some(game.current_player()),
+            }
+            .map(|p| p.name.clone())
+            .unwrap_or_default();
+            replay::apply(&mut game, event).expect("replays are checked when loaded");
+            log.push(match event {
+                Event::Roll { dice } => {
+                    let dice: Vec<String> = dice.iter().map(u8::to_string).collect();
+                    format!("{name} rolls {}", dice.join(" "))
+                }
+                Event::Hold { die } if game.dice()[*die].held => format!("{name} holds die {}", die + 1),
+                Event::Hold { die } => format!("{name} releases die {}", die + 1),
+                Event::Score {
+                    category,
+                    points,
+                    column,
+                } => format!("{name} scores {points} in {}", self.box_label(*column, *category)),
+                Event::Undo => format!("{name} takes back their last score"),
+                Event::Forfeit => format!("{name} forfeits"),
+            });
+        }
+        let rolled = animate && matches!(playback.last_event(), Some(Event::Roll { .. }));
+        let interval = playback.interval().as_millis() as u64;
+
+        self.game = game;
+        self.dice_faces = self.game.dice().to_vec();
+        self.log.clear();
+        for entry in log {
+            self.log(entry);
+        }
+        if rolled {
+            self.animate_roll(interval / 4, interval / 2);
+        }
+    }
+
     fn render_main(&self, area: Rect, buf: &mut Buffer) {
         let main_layout = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Length(21), Constraint::Length(1)])
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
             .split(area);
 
-        let title = Line::from(" YAHTZEE ".bold());
+        let title = match self.daily {
+            Some(date) if !self.daily_counts => format!(" DAILY {date} PRACTICE "),
+            Some(date) => format!(" DAILY {date} "),
+            None => format!(" {} ", Variant::of(&self.game).to_string().to_uppercase()),
+        };
+        let title = Line::from(title.bold());
         let block = Block::bordered()
             .title(title.centered())
             .fg(Theme::BORDER)
             .border_set(border::THICK);
 
-        let inner = block.inner(area);
+        let inner = block.inner(main_layout[0]);
         block.render(main_layout[0], buf);
         self.render_footer(main_layout[1], buf);
 
+        match self.screen {
+            Screen::Title => return self.render_title(inner, buf),
+            Screen::Setup => return self.render_setup(inner, buf),
+            Screen::HighScores => return self.render_high_scores(inner, buf),
+            Screen::Statistics => return self.render_stats(inner, buf),
+            Screen::Settings => return self.render_settings(inner, buf),
+            Screen::Help => return self.render_help(inner, buf),
+            Screen::Standings => return self.render_standings(inner, buf),
+            Screen::Log => return self.render_log(inner, buf),
+            Screen::Playing | Screen::Replay if self.is_multiplayer() && self.is_game_over() => {
+                return self.render_rankings(inner, buf);
+            }
+            Screen::Playing | Screen::NameEntry | Screen::Replay => {}
+        }
+
+        let slots_height = self.slots_height();
         let sections = Layout::default() //slot section and bottom section with dice and score
             .direction(Direction::Vertical)
             .spacing(1)
-            .constraints([Constraint::Length(9), Constraint::Length(5)])
+            .constraints([Constraint::Length(slots_height), Constraint::Length(5)])
             .split(inner);
 
         self.render_slots(sections[0], buf);
 
-        if self.is_game_over() {
+        if self.screen == Screen::NameEntry {
+            self.render_name_entry(sections[1], buf);
+        } else if self.is_game_over() {
             self.render_game_over(sections[1], buf);
         } else {
             self.render_dice_and_score(sections[1], buf);
         }
     }
 
+    fn render_title(&self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(1),
+                Constraint::Length(2),
+                Constraint::Length(self.title_menu().len() as u16),
+                Constraint::Min(1),
+            ])
+            .split(area);
+
+        Line::from("Roll the dice, fill the card".fg(Theme::TEXT_DIM))
+            .centered()
+            .render(layout[1], buf);
+        Menu::new(&self.title_menu(), self.menu_index).render(layout[2], buf);
+    }
+
+    fn render_setup(&self, area: Rect, buf: &mut Buffer) {
+        let options = [("Mode", self.mode.to_string()), ("Rules", self.ruleset.to_string())];
+        let mut lines = vec![Line::from("New Game".fg(Theme::ACCENT).bold()), Line::from("")];
+        for (i, (label, value)) in options.into_iter().enumerate() {
+            lines.push(if i == self.setup_index {
+                Line::from(format!("{label}: ◂ {value} ▸")).fg(Theme::ACCENT).bold()
+            } else {
+                Line::from(vec![format!("{label}: ").fg(Theme::TEXT), value.fg(Theme::PRIMARY)])
+            });
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!("Players (1-{MAX_PLAYERS})").fg(Theme::ACCENT).bold()));
+        for (i, player) in self.lineup.iter().enumerate() {
+            let kind = match (&player.command, player.bot) {
+                (Some(_), _) => String::from("(program)"),
+                (None, Some(difficulty)) => format!("({difficulty} bot)"),
+                (None, None) => String::new(),
+            };
+            let line = if Some(i) == self.setup_player() {
+                Line::from(vec![
+                    format!("{}. ", i + 1).fg(Theme::ACCENT),
+                    player.name.clone().fg(Theme::ACCENT).bold(),
+                    "█ ".fg(Theme::ACCENT),
+                    kind.fg(Theme::TEXT_DIM),
+                ])
+            } else {
+                Line::from(vec![
+                    format!("{}. {} ", i + 1, player.name).fg(Theme::TEXT),
+                    kind.fg(Theme::TEXT_DIM),
+                ])
+            };
+            lines.push(line);
+        }
+
+        Paragraph::new(lines).centered().render(area, buf);
+    }
+
+    fn render_standings(&self, area: Rect, buf: &mut Buffer) {
+        let current = (!self.is_game_over()).then(|| self.game.current_player_index());
+        Standings::new(self.game.players(), current).render(area.inner(Margin::new(1, 0)), buf);
+    }
+
+    fn render_log(&self, area: Rect, buf: &mut Buffer) {
+        let [header, entries] =
+            Layout::vertical([Constraint::Length(2), Constraint::Min(0)]).areas(area);
+        Line::from("Game Log".fg(Theme::ACCENT).bold())
+            .centered()
+            .render(header, buf);
+
+        // the latest entries that fit, oldest at the top
+        let shown = self.log.len().min(entries.height.into());
+        let lines: Vec<Line> = self.log[self.log.len() - shown..]
+            .iter()
+            .map(|entry| Line::from(entry.clone().fg(Theme::TEXT)))
+            .collect();
+        Paragraph::new(lines).render(entries.inner(Margin::new(2, 0)), buf);
+    }
+
+    fn render_rankings(&self, area: Rect, buf: &mut Buffer) {
+        let standings = player::standings(self.game.players());
+        let mut lines = vec![
+            Line::from("Final Standings".fg(Theme::ACCENT).bold()),
+            Line::from(""),
+        ];
+        for (i, (rank, player)) in standings.iter().enumerate() {
+            let tied = standings
+                .iter()
+                .enumerate()
+                .any(|(j, (other, _))| j != i && other == rank);
+            let color = if *rank == 1 { Theme::ACCENT } else { Theme::TEXT };
+            lines.push(Line::from(vec![
+                format!("{}{:<2} ", if tied { "=" } else { " " }, rank).fg(color),
+                format!("{:<16}", player.name).fg(color),
+                format!("{:>4}", player.total_score()).fg(Theme::PRIMARY).bold(),
+                if player.forfeited { " out" } else { "    " }.fg(Theme::TEXT_DIM),
+            ]));
+        }
+        if let Some(note) = self.undo_note() {
+            lines.push(Line::from(""));
+            lines.push(Line::from(note.fg(Theme::TEXT_DIM)));
+        }
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(2), Constraint::Min(1)])
+            .split(area);
+        Paragraph::new(lines).centered().render(layout[1], buf);
+    }
+
+    fn render_high_scores(&self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(2), Constraint::Min(1)])
+            .split(area.inner(Margin::new(1, 0)));
+
+        let variant = self.high_score_variant();
+        let title = if variant == Variant::default() {
+            String::from("High Scores")
+        } else {
+            format!("{variant} High Scores")
+        };
+        Line::from(title.fg(Theme::ACCENT).bold())
+            .centered()
+            .render(layout[0], buf);
+        let table = self.high_scores.table(variant);
+        HighScoreTable::new(&table, self.new_high_score).render(layout[1], buf);
+    }
+
+    fn render_stats(&self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(1)])
+            .split(area.inner(Margin::new(1, 0)));
+
+        // like the high scores, the statistics are those of the next game's variant
+        let variant = self.high_score_variant();
+        let title = if variant == Variant::default() {
+            String::from("Statistics")
+        } else {
+            format!("{variant} Statistics")
+        };
+        Line::from(title.fg(Theme::ACCENT).bold())
+            .centered()
+            .render(layout[0], buf);
+        let summary = self.stats.summary(variant);
+        StatsPanel::new(summary.as_ref(), variant).render(layout[1], buf);
+    }
+
+    fn render_settings(&self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(1),
+                Constraint::Length(2),
+                Constraint::Length(SETTINGS_MENU.len() as u16),
+                Constraint::Min(1),
+            ])
+            .split(area);
+
+        let on_off = |on: bool| if on { "On" } else { "Off" };
+        let values = [
+            on_off(self.settings.one_undo_per_turn).to_string(),
+            on_off(self.settings.hints).to_string(),
+            self.settings.bot_speed.to_string(),
+        ];
+        let items: Vec<String> = SETTINGS_MENU
+            .iter()
+            .zip(values)
+            .map(|(label, value)| format!("{label}: {value}"))
+            .collect();
+        let items: Vec<&str> = items.iter().map(String::as_str).collect();
+        Line::from("Settings".fg(Theme::ACCENT).bold())
+            .centered()
+            .render(layout[1], buf);
+        Menu::new(&items, self.settings_index).render(layout[2], buf);
+    }
+
+    fn render_help(&self, area: Rect, buf: &mut Buffer) {
+        let area = area.inner(Margin::new(2, 0));
+        let mut rules = String::from(
+            "Fill in every box of the scorecard. Each turn, roll the dice, hold those \
+             worth keeping and roll the rest again, then score them in an open box. \
+             The highest total wins.",
+        );
+        match self.mode {
+            Mode::Standard => {}
+            Mode::Triple => rules.push_str(" In Triple games the three columns count once, twice and three times."),
+            Mode::Ordered => rules.push_str(" In Ordered games the boxes are filled top to bottom."),
+        }
+
+        let keys = &self.keymap;
+        let bound: Vec<(String, &str)> = [
+            (keys.label(Action::Roll), "Roll"),
+            (keys.hold_label(self.ruleset.dice_count()), "Hold a die"),
+            (keys.labels(&[Action::Prev, Action::Next, Action::Up, Action::Down]), "Pick a box"),
+            (keys.label(Action::Score), "Score it"),
+            (keys.label(Action::Menu), "Menu"),
+            (keys.label(Action::Undo), "Undo"),
+            (keys.label(Action::Hint), "Hint"),
+            (keys.label(Action::Table), "Table"),
+            (keys.label(Action::Log), "Log"),
+            (keys.label(Action::Quit), "Quit"),
+        ]
+        .into_iter()
+        .filter_map(|(key, action)| Some((key?, action)))
+        .collect();
+        let (left, right) = bound.split_at(bound.len().div_ceil(2));
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Min(1),
+                Constraint::Length(left.len() as u16),
+            ])
+            .split(area);
+
+        Line::from("How to Play".fg(Theme::ACCENT).bold())
+            .centered()
+            .render(layout[0], buf);
+        Paragraph::new(rules.fg(Theme::TEXT))
+            .wrap(Wrap { trim: true })
+            .render(layout[1], buf);
+        let key_width = |keys: &[(String, &str)]| keys.iter().map(|(key, _)| key.chars().count()).max().unwrap_or(0) as u16;
+        let rows = left.iter().zip(right.iter().map(Some).chain(iter::repeat(None))).map(|((key, action), other)| {
+            let (other_key, other_action) = other.map_or(("", ""), |(key, action)| (key.as_str(), *action));
+            Row::new([
+                Text::from(key.clone().fg(Theme::SECONDARY).bold()),
+                Text::from(action.fg(Theme::TEXT)),
+                Text::from(other_key.fg(Theme::SECONDARY).bold()),
+                Text::from(other_action.fg(Theme::TEXT)),
+            ])
+        });
+        let (left_width, right_width) = (key_width(left), key_width(right));
+        let widths = [
+            Constraint::Length(left_width),
+            Constraint::Length(12),
+            Constraint::Length(right_width),
+            Constraint::Length(5),
+        ];
+        // the columns and the spaces between them
+        let [table_area] = Layout::horizontal([Constraint::Length(left_width + right_width + 20)])
+            .flex(Flex::Center)
+            .areas(layout[2]);
+        Widget::render(Table::new(rows, widths).column_spacing(1), table_area, buf);
+    }
+
+    fn render_name_entry(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title(Line::from("New High Score!").centered())
+            .fg(Theme::ACCENT)
+            .border_set(border::DOUBLE);
+        let inner_area = block.inner(area);
+        block.render(area, buf);
+
+        Paragraph::new(vec![
+            Line::from(vec![
+                "Score: ".fg(Theme::TEXT),
+                format!("{}", self.total_score()).fg(Theme::PRIMARY),
+            ]),
+            Line::from(vec![
+                "Name: ".fg(Theme::TEXT),
+                self.name_input.clone().fg(Theme::PRIMARY).bold(),
+                "█".fg(Theme::ACCENT),
+            ]),
+        ])
+        .centered()
+        .render(inner_area, buf);
+    }
+
     fn render_game_over(&self, area: Rect, buf: &mut Buffer) {
         let block = Block::bordered()
             .title(Line::from("Game Over").centered())
@@ -146,58 +1277,127 @@ impl App {
         let inner_area = block.inner(area);
         block.render(area, buf);
 
+        let mut score = Line::from(vec![
+            "Score: ".fg(Theme::TEXT),
+            format!("{}", self.total_score()).fg(Theme::PRIMARY),
+        ]);
+        if let Some(note) = self.undo_note() {
+            score.push_span(format!("  {note}").fg(Theme::TEXT_DIM));
+        }
+        let mut lines = vec![score];
+        if let Some(date) = self.daily {
+            let today = match self.daily_results.scores.get(&date) {
+                Some(score) => score.to_string(),
+                None => String::from("given up"),
+            };
+            let streak = self.daily_results.streak(date);
+            lines.push(Line::from(vec![
+                if self.daily_counts { "Today's score: " } else { "Practice, today's score: " }.fg(Theme::TEXT),
+                today.fg(Theme::PRIMARY),
+                "  Streak: ".fg(Theme::TEXT),
+                format!("{streak} {}", if streak == 1 { "day" } else { "days" }).fg(Theme::PRIMARY),
+            ]));
+            let rolls = self.game.current_player().rolls();
+            lines.push(Line::from(format!("{} {}", daily::grid(rolls, true), daily::grid(rolls, false))));
+        }
+        let house_rules = self.game.house_rules();
+        if !house_rules.is_default() {
+            lines.push(Line::from(format!("House rules: {house_rules}").fg(Theme::TEXT_DIM)));
+        }
+
         // Create vertical centering layout
         let vertical_center = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Min(1),
-                Constraint::Length(1),
-                Constraint::Min(1),
+                Constraint::Min(0),
+                Constraint::Length(lines.len() as u16),
+                Constraint::Min(0),
             ])
             .split(inner_area);
 
-        Paragraph::new(vec![Line::from(vec![
-            "Score: ".fg(Theme::TEXT),
-            format!("{}", self.total_score()).fg(Theme::PRIMARY),
-        ])])
+        Paragraph::new(lines)
         .centered()
         .render(vertical_center[1], buf);
     }
 
+    /// Marks finished games in which a score was taken back.
+    fn undo_note(&self) -> Option<String> {
+        match self.game.undo_count() {
+            0 => None,
+            1 => Some(String::from("(undo used once)")),
+            count => Some(format!("(undo used {count} times)")),
+        }
+    }
+
     fn render_slots(&self, area: Rect, buf: &mut Buffer) {
+        let player = self.game.current_player();
         let roll_slots = RollSlots {
-            rolls: self.rolls,
+            columns: &player.columns,
+            column: player.active_column(),
             faces: &self.dice_faces,
-            roll_count: self.roll_count,
+            roll_count: self.game.roll_count(),
+            ordered: self.game.mode() == Mode::Ordered,
+            player_name: self
+                .is_multiplayer()
+                .then(|| self.game.current_player().name.as_str()),
+            last_action: self.log.last().map(String::as_str),
         };
         roll_slots.render(area, buf);
     }
 
+    /// Works out the hint if it is shown and the game has rolled, held or
+    /// scored since, so drawing only reads it.
+    fn update_hint(&mut self) {
+        let shown = self.show_hint && self.has_hints();
+        let Some(solver) = self.solver.as_ref().filter(|_| shown) else {
+            self.hint = None;
+            return;
+        };
+        let key = HintKey::of(&self.game);
+        if self.hint.as_ref().is_none_or(|(old, _)| *old != key) {
+            self.hint = Some((key, Hint::new(solver, &self.game)));
+        }
+    }
+
     fn render_dice_and_score(&self, area: Rect, buf: &mut Buffer) {
         let layout = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([45, 11])
+            .constraints([Dice::width(self.dice_faces.len()), 11])
             .split(area);
 
-        let dice = Dice::new(self.dice_faces.clone());
+        let mut dice = Dice::new(self.dice_faces.clone());
+        if self.show_hint && self.has_hints() && !self.is_rolling() {
+            let hint = self.hint.as_ref().map(|(_, hint)| hint);
+            if let Some(hold) = hint.and_then(|h| h.hold.clone()) {
+                dice = dice.suggested(hold);
+            }
+            dice.render(layout[0], buf);
+            HintPanel {
+                hint,
+                score: self.total_score(),
+                keymap: &self.keymap,
+            }
+            .render(layout[1], buf);
+            return;
+        }
         dice.render(layout[0], buf);
 
-        let filled = "●";
-        let empty = "○";
+        let roll_count = self.game.roll_count();
+        let rolls_per_turn = self.game.rolls_per_turn();
+        // saved rolls can add up to more dots than there is room for
+        let rolls = if rolls_per_turn <= 5 {
+            (0..rolls_per_turn)
+                .map(|i| if i < roll_count { "●" } else { "○" })
+                .collect()
+        } else {
+            format!("{roll_count}/{rolls_per_turn}")
+        };
         Paragraph::new(vec![
             Line::from(""),
             Line::from(""),
             Line::from(vec![
                 "Roll: ".fg(Theme::TEXT),
-                if self.roll_count > 0 { filled } else { empty }
-                    .fg(Theme::PRIMARY)
-                    .bold(),
-                if self.roll_count > 1 { filled } else { empty }
-                    .fg(Theme::PRIMARY)
-                    .bold(),
-                if self.roll_count > 2 { filled } else { empty }
-                    .fg(Theme::PRIMARY)
-                    .bold(),
+                rolls.fg(Theme::PRIMARY).bold(),
             ]),
             Line::from(vec![
                 "SCORE: ".fg(Theme::TEXT),
@@ -208,84 +1408,392 @@ impl App {
         .render(layout[1], buf);
     }
 
+    /// The footer lists the keys of the screen, as bound in the keymap.
     fn render_footer(&self, area: Rect, buf: &mut Buffer) {
-        let instructions = match self.is_game_over() {
-            false => Line::from(vec![
-                "Quit ".fg(Theme::TEXT),
-                "q ".fg(Theme::SECONDARY).bold(),
-                "| Roll ".fg(Theme::TEXT),
-                "r ".blue().bold(),
-                "| (Un)Hold ".fg(Theme::TEXT),
-                "1-5 ".fg(Theme::SECONDARY).bold(),
-                "| Move ".fg(Theme::TEXT),
-                "arrows ".fg(Theme::SECONDARY).bold(),
-                "| Select ".fg(Theme::TEXT),
-                "CR".fg(Theme::SECONDARY).bold(),
-            ]),
-            true => Line::from(vec![
-                "Quit ".fg(Theme::TEXT),
-                "q ".blue().bold(),
-                "| Play Again ".fg(Theme::TEXT),
-                "CR".blue().bold(),
-            ]),
+        let keys = &self.keymap;
+        let mut prefix = None;
+        let mut suffix = None;
+        let entries = match self.screen {
+            Screen::Title => vec![
+                ("Move", keys.labels(&[Action::Up, Action::Down])),
+                ("Select", keys.label(Action::Score)),
+            ],
+            Screen::Setup => vec![
+                ("Change", keys.labels(&[Action::Prev, Action::Next])),
+                ("Add", keys.label(Action::AddPlayer)),
+                ("Remove", keys.label(Action::RemovePlayer)),
+                ("Play", keys.label(Action::Score)),
+                ("Back", keys.label(Action::Menu)),
+            ],
+            Screen::Settings => vec![
+                ("Move", keys.labels(&[Action::Up, Action::Down])),
+                ("Change", keys.labels(&[Action::Prev, Action::Next])),
+                ("Back", keys.label(Action::Menu)),
+            ],
+            Screen::HighScores | Screen::Statistics | Screen::Help => vec![("Back", keys.label(Action::Menu))],
+            Screen::Standings => vec![("Back", keys.label(Action::Table)), ("Log", keys.label(Action::Log))],
+            Screen::Log => vec![("Back", keys.label(Action::Log))],
+            Screen::NameEntry => vec![("Save", keys.label(Action::Score)), ("Skip", keys.label(Action::Menu))],
+            Screen::Replay => {
+                let (step, len, speed, paused) = self
+                    .playback
+                    .as_ref()
+                    .map_or((0, 0, 1.0, true), |p| (p.step, p.replay.len(), p.speed(), p.paused));
+                prefix = Some(format!("{step}/{len} x{speed} {} | ", if paused { "⏸" } else { "▶" }).fg(Theme::PRIMARY));
+                vec![
+                    ("Pause", keys.label(Action::Pause)),
+                    ("Step", keys.labels(&[Action::Prev, Action::Next])),
+                    ("Speed", keys.labels(&[Action::Slower, Action::Faster])),
+                    ("Quit", keys.label(Action::Quit)),
+                ]
+            }
+            Screen::Playing if self.is_game_over() => vec![
+                ("Quit", keys.label(Action::Quit)),
+                ("Table", keys.label(Action::Table).filter(|_| self.is_multiplayer())),
+                ("Menu", keys.label(Action::Menu)),
+                ("Play Again", keys.label(Action::Score)),
+            ],
+            Screen::Playing if self.is_remote_turn() => {
+                if self.is_spectating() {
+                    prefix = Some("Watching | ".fg(Theme::TEXT_DIM));
+                }
+                suffix = Some(format!(" | {}'s turn", self.game.current_player().name).fg(Theme::TEXT_DIM));
+                vec![
+                    ("Quit", keys.label(Action::Quit)),
+                    ("Table", keys.label(Action::Table)),
+                    ("Log", keys.label(Action::Log)),
+                ]
+            }
+            Screen::Playing => {
+                let mut entries = vec![("Quit", keys.label(Action::Quit)), ("Roll", keys.label(Action::Roll))];
+                if self.can_undo() {
+                    entries.push(("Undo", keys.label(Action::Undo)));
+                } else {
+                    let hold = if self.is_multiplayer() { "Hold" } else { "(Un)Hold" };
+                    entries.push((hold, keys.hold_label(self.dice_faces.len())));
+                    entries.push(("Pick", self.pick_keys()));
+                }
+                if self.is_multiplayer() {
+                    entries.push(("Table", keys.label(Action::Table)));
+                }
+                if self.has_hints() {
+                    entries.push(("Hint", keys.label(Action::Hint)));
+                }
+                entries
+            }
         };
 
-        instructions.centered().render(area, buf);
+        let mut entries: Vec<(&str, String)> = entries.into_iter().filter_map(|(a, k)| Some((a, k?))).collect();
+        // long keys leave out the last entries rather than running off the screen
+        loop {
+            let mut spans: Vec<Span> = prefix.iter().cloned().collect();
+            for (i, (action, key)) in entries.iter().enumerate() {
+                let separator = if i == 0 { "" } else { " | " };
+                spans.push(format!("{separator}{action} ").fg(Theme::TEXT));
+                spans.push(key.clone().fg(Theme::SECONDARY).bold());
+            }
+            spans.extend(suffix.iter().cloned());
+            let line = Line::from(spans);
+            if line.width() <= usize::from(area.width) || entries.len() <= 1 {
+                return line.centered().render(area, buf);
+            }
+            entries.pop();
+        }
+    }
+
+    /// Keys that move the selection and score it.
+    fn pick_keys(&self) -> Option<String> {
+        let moves: &[Action] = match self.game.mode() {
+            Mode::Standard => &[Action::Prev, Action::Next],
+            Mode::Triple => &[Action::Prev, Action::Next, Action::Up, Action::Down],
+            Mode::Ordered => &[],
+        };
+        match (self.keymap.labels(moves), self.keymap.label(Action::Score)) {
+            (Some(moves), Some(score)) => Some(format!("{moves} {score}")),
+            (moves, score) => moves.or(score),
+        }
     }
 
     pub fn toggle_hold(&mut self, index: usize) {
-        self.dice_faces[index].held = !self.dice_faces[index].held;
+        if let Some(Session::Guest(guest)) = &mut self.net {
+            return guest.send(&Command::Hold { die: index });
+        }
+        if self.game.hold(index).is_ok() {
+            self.dice_faces[index].held = self.game.dice()[index].held;
+        }
+    }
+
+    pub fn submit_selection(&mut self) -> io::Result<()> {
+        let Some(selection) = self.game.rolls_mut().selected().copied() else {
+            return Ok(());
+        };
+        let name = self.game.current_player().name.clone();
+        let column = self.game.current_player().active_column();
+        if let Some(Session::Guest(guest)) = &mut self.net {
+            guest.send(&Command::Score {
+                column,
+                category: selection.roll_type,
+            });
+            return Ok(());
+        }
+        if let Ok(score) = self.game.score_in(column, selection.roll_type) {
+            self.show_score(&name, column, selection.roll_type, score)?;
+        }
+        Ok(())
     }
 
-    pub fn submit_selection(&mut self) {
-        if self.rolls.selected().is_some() {
-            if self.rolls.yahtzee_roll.score.is_some() {
-                // if yahtzee already scored, always check for bonus yahtzee
-                self.rolls.yahtzee_roll.score = Some(calc_score(self.rolls.yahtzee_roll, &self.dice_faces));
+    /// Logs a score that was just made, wrapping up the game if it was the last.
+    fn show_score(&mut self, name: &str, column: usize, roll_type: RollType, score: u32) -> io::Result<()> {
+        self.log_score(name, column, roll_type, score);
+        self.dice_faces = self.game.dice().to_vec();
+        self.finish_game_if_over()
+    }
+
+    /// Stores the replay and records of a game that just ended.
+    fn finish_game_if_over(&mut self) -> io::Result<()> {
+        if self.is_game_over() {
+            if let Some(replay) = Replay::from_game(&self.game, self.seed) {
+                replay.store_new()?;
             }
-            let selection = self.rolls.selected().unwrap();
-            selection.score = Some(calc_score(*selection, &self.dice_faces).into());
-            self.reset();
+            // only the games played at this terminal count towards its records
+            if self.net.is_none() {
+                self.record_stats()?;
+                self.record_high_scores()?;
+                self.record_daily()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds every human player's scorecard to the statistics.
+    fn record_stats(&mut self) -> io::Result<()> {
+        let variant = Variant::of(&self.game);
+        for player in self.game.players().iter().filter(|p| !p.is_bot()) {
+            self.stats.games.push(GameRecord::new(player, variant));
         }
+        self.stats.store()
+    }
+
+    /// Keeps the score of the day's first try at the daily challenge.
+    fn record_daily(&mut self) -> io::Result<()> {
+        let Some(date) = self.daily.filter(|_| self.daily_counts) else {
+            return Ok(());
+        };
+        self.daily_results.record(date, self.total_score());
+        self.daily_results.store()
+    }
+
+    /// The result of a finished daily challenge, to paste into a chat.
+    pub fn daily_share_text(&self) -> Option<String> {
+        let date = self.daily.filter(|_| self.is_game_over() && self.daily_counts)?;
+        let streak = self.daily_results.streak(date);
+        Some(daily::share_text(date, self.game.current_player(), streak))
+    }
+
+    fn log_score(&mut self, name: &str, column: usize, roll_type: RollType, score: u32) {
+        self.log(format!("{name} scores {score} in {}", self.box_label(column, roll_type)));
+    }
+
+    /// Names a scorecard box, with its column in Triple games.
+    fn box_label(&self, column: usize, roll_type: RollType) -> String {
+        let label = short_label(roll_type, self.game.ruleset());
+        if self.is_triple() {
+            format!("{label} x{}", Player::weight(column))
+        } else {
+            label.to_string()
+        }
+    }
+
+    /// The high score table shown, which is that of the next game.
+    fn high_score_variant(&self) -> Variant {
+        Variant {
+            mode: self.mode,
+            ruleset: self.ruleset,
+            house_rules: self.house_rules,
+        }
+    }
+
+    /// Asks a solitaire player for their name if they made the high score
+    /// table; hot-seat players are added under the names they played as.
+    /// Computer players never enter the table.
+    fn record_high_scores(&mut self) -> io::Result<()> {
+        if self.is_multiplayer() {
+            for player in self.game.players().iter().filter(|p| !p.is_bot()) {
+                self.high_scores.insert(HighScore::new(&player.name, player, Variant::of(&self.game)));
+            }
+            self.high_scores.store()
+        } else if self.game.current_player().is_bot() {
+            Ok(())
+        } else {
+            if self.high_scores.qualifies(Variant::of(&self.game), self.total_score()) {
+                self.name_input.clear();
+                self.screen = Screen::NameEntry;
+            }
+            Ok(())
+        }
+    }
+
+    pub fn push_name_char(&mut self, c: char) {
+        if self.name_input.chars().count() < MAX_NAME_LEN {
+            self.name_input.push(c);
+        }
+    }
+
+    pub fn pop_name_char(&mut self) {
+        self.name_input.pop();
+    }
+
+    /// Adds the finished game to the high score table under the entered name.
+    pub fn submit_name(&mut self) -> io::Result<()> {
+        let name = match self.name_input.trim() {
+            "" => "Player",
+            name => name,
+        };
+        let index = self
+            .high_scores
+            .insert(HighScore::new(name, self.game.current_player(), Variant::of(&self.game)));
+        self.high_scores.store()?;
+        self.show_high_scores(index);
+        Ok(())
+    }
+
+    /// The seat at the keyboard: whoever's turn it is, or while bots take
+    /// their turns, the person who handed the dice to them.
+    fn keyboard_seat(&self) -> Option<usize> {
+        if !self.game.current_player().is_bot() {
+            return Some(self.game.current_player_index());
+        }
+        self.game.last_scorer().filter(|seat| !self.game.players()[*seat].is_bot())
+    }
+
+    /// Whether the last score can be taken back by the player at the
+    /// keyboard. Bots stand by their moves, in hot-seat games the next
+    /// player cannot take back the score of the one before, and the daily
+    /// challenge has no second chances.
+    pub fn can_undo(&self) -> bool {
+        self.net.is_none()
+            && self.daily.is_none()
+            && self.keyboard_seat().is_some_and(|seat| self.game.can_undo(seat).is_ok())
+    }
+
+    pub fn undo(&mut self) {
+        if !self.can_undo() {
+            return;
+        }
+        let Some(seat) = self.game.last_scorer() else {
+            return;
+        };
+        let name = self.game.players()[seat].name.clone();
+        if self.game.undo(seat).is_ok() {
+            self.dice_faces = self.game.dice().to_vec();
+            self.log(format!("{name} takes back their last score"));
+        }
+    }
+
+    pub fn skip_name_entry(&mut self) {
+        self.screen = Screen::Playing;
     }
 
     pub fn total_score(&self) -> u32 {
-        let total = self.rolls
-            .iter()
-            .fold(0, |tot, r| tot + r.score.unwrap_or(0));
-        let (_, bonus) = self.rolls.bonus_status();
-        return total + bonus;
+        self.game.total_score()
+    }
+
+    /// Starts today's challenge, the same for everyone playing today.
+    pub fn start_daily(&mut self) {
+        self.daily = Some(Local::now().date_naive());
+        self.start_over();
+        self.show_board();
     }
 
-    pub fn reset(&mut self) {
-        for f in &mut self.dice_faces {
-            f.held = false
+    /// Goes back to dice nobody else is rolling.
+    fn leave_daily(&mut self) {
+        if self.daily.take().is_some() {
+            self.daily_counts = false;
+            self.roller = Box::new(SeededRoller::new(rand::random()));
+            self.seed = None;
         }
-        self.roll_count = 0;
-        self.rolls.clear_selection();
     }
 
+    /// Starts a new game with the configured players, or another try at
+    /// the daily challenge.
     pub fn start_over(&mut self) {
-        self.reset();
-        self.rolls = AllRolls::new();
+        // a network game is not played again, the connection ends with it
+        self.net = None;
+        if let Some(date) = self.daily {
+            return self.start_daily_on(date);
+        }
+        let players = self
+            .lineup
+            .iter()
+            .enumerate()
+            .map(|(i, player)| {
+                let name = match player.name.trim() {
+                    "" => format!("Player {}", i + 1),
+                    name => name.to_string(),
+                };
+                match (&player.command, player.bot) {
+                    (Some(command), _) => Player::external(name, command),
+                    (None, Some(difficulty)) => Player::bot(name, difficulty),
+                    (None, None) => Player::new(name),
+                }
+            })
+            .collect();
+        if self.game.has_started() {
+            // the seed only reproduces the first game rolled with it
+            self.seed = None;
+        }
+        self.game = Game::with_players(players)
+            .with_ruleset(self.ruleset)
+            .with_house_rules(self.house_rules)
+            .with_mode(self.mode)
+            .with_one_undo_per_turn(self.settings.one_undo_per_turn);
+        self.dice_faces = self.game.dice().to_vec();
+        self.reset_bots();
+    }
+
+    /// A solitaire standard game with the dice seeded from `date`, so the
+    /// same holds roll the same dice for everyone.
+    fn start_daily_on(&mut self, date: NaiveDate) {
+        let name = self
+            .lineup
+            .iter()
+            .filter(|p| !p.is_bot())
+            .map(|p| p.name.trim())
+            .find(|name| !name.is_empty())
+            .unwrap_or("Player 1");
+        let seed = daily::seed_for(date);
+        self.daily_counts = !self.daily_results.is_tried(date);
+        self.roller = Box::new(SeededRoller::new(seed));
+        self.seed = Some(seed);
+        self.game = Game::with_players(vec![Player::new(name)])
+            .with_one_undo_per_turn(self.settings.one_undo_per_turn);
+        self.dice_faces = self.game.dice().to_vec();
+        self.reset_bots();
     }
 
     pub fn is_game_over(&self) -> bool {
-        self.rolls.iter().all(|r| r.score.is_some())
+        self.game.is_game_over()
     }
 }
 
+/// The item after `current` in `items`, or the one before it, wrapping around.
+fn cycle<T: Copy + PartialEq>(items: &[T], current: T, forward: bool) -> T {
+    let index = items.iter().position(|item| *item == current).unwrap_or(0);
+    let next = if forward { index + 1 } else { index + items.len() - 1 };
+    items[next % items.len()]
+}
+
 pub fn draw(app: &App, frame: &mut Frame) {
     frame.render_widget(app, frame.area());
 }
 
 impl Widget for &App {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        if area.width < WIDTH.into() || area.height < HEIGHT.into() {
+        if area.width < self.width() || area.height < self.height() {
             Line::from("Terminal window too small".red().bold()).render(area, buf);
         } else {
-            let width = area.width.min(WIDTH.into());
-            let height = area.height.min(HEIGHT.into());
+            let width = area.width.min(self.width());
+            let height = area.height.min(self.height());
             let x = (area.width.saturating_sub(width)) / 2;
             let y = (area.height.saturating_sub(height)) / 2;
             let constrained_area = Rect::new(x, y, width, height);
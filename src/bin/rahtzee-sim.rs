@@ -0,0 +1,71 @@
+use std::{env, process, sync::Arc, thread};
+
+use rahtzee::{
+    bot::Difficulty,
+    sim::{self, SimConfig},
+    solver::Solver,
+};
+
+const USAGE: &str =
+    "Usage: rahtzee-sim [--games N] [--strategy LEVEL] [--seed N] [--threads N] [--json]";
+
+struct Args {
+    config: SimConfig,
+    json: bool,
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args {
+        config: SimConfig {
+            games: 1000,
+            strategy: Difficulty::Heuristic,
+            seed: rand::random(),
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        },
+        json: false,
+    };
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("{name} needs a value"));
+        match arg.as_str() {
+            "--games" => {
+                let value = value("--games")?;
+                parsed.config.games = value.parse().map_err(|_| format!("invalid game count: {value}"))?;
+            }
+            "--strategy" => parsed.config.strategy = value("--strategy")?.parse()?,
+            "--seed" => {
+                let value = value("--seed")?;
+                parsed.config.seed = value.parse().map_err(|_| format!("invalid seed: {value}"))?;
+            }
+            "--threads" => {
+                let value = value("--threads")?;
+                parsed.config.threads = match value.parse() {
+                    Ok(threads) if threads > 0 => threads,
+                    _ => return Err(format!("invalid thread count: {value}")),
+                };
+            }
+            "--json" => parsed.json = true,
+            _ => return Err(format!("unknown argument: {arg}")),
+        }
+    }
+    Ok(parsed)
+}
+
+fn main() {
+    let args = parse_args(env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{err}\n{USAGE}");
+        process::exit(2);
+    });
+
+    let solver = args.config.strategy.needs_solver().then(|| {
+        eprintln!("Loading the strategy table...");
+        Arc::new(Solver::load_or_build())
+    });
+    let report = sim::run(&args.config, solver);
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report).expect("reports always serialize"));
+    } else {
+        print!("{report}");
+    }
+}
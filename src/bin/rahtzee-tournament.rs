@@ -0,0 +1,118 @@
+use std::{env, fs, path::PathBuf, process, sync::Arc, thread};
+
+use rahtzee::{
+    solver::Solver,
+    tournament::{self, Entrant, Format, TournamentConfig},
+};
+
+const USAGE: &str = "Usage: rahtzee-tournament [--bots LEVEL,LEVEL,...] [--bot-cmd PROGRAM]... [--format round-robin|swiss] [--rounds N] [--games N] [--bye-points N] [--seed N] [--threads N] [--json FILE]";
+
+struct Args {
+    config: TournamentConfig,
+    rounds: Option<usize>,
+    bye_points: Option<f64>,
+    json: Option<PathBuf>,
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args {
+        config: TournamentConfig {
+            entrants: Vec::new(),
+            format: Format::RoundRobin,
+            rounds: 0,
+            games: 100,
+            bye_points: 0.0,
+            seed: rand::random(),
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        },
+        rounds: None,
+        bye_points: None,
+        json: None,
+    };
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("{name} needs a value"));
+        match arg.as_str() {
+            "--bots" => {
+                for level in value("--bots")?.split(',') {
+                    parsed.config.entrants.push(Entrant::bot(level.parse()?));
+                }
+            }
+            "--bot-cmd" => parsed.config.entrants.push(Entrant::program(value("--bot-cmd")?)),
+            "--format" => parsed.config.format = value("--format")?.parse()?,
+            "--rounds" => {
+                let value = value("--rounds")?;
+                parsed.rounds = match value.parse() {
+                    Ok(rounds) if rounds > 0 => Some(rounds),
+                    _ => return Err(format!("invalid round count: {value}")),
+                };
+            }
+            "--games" => {
+                let value = value("--games")?;
+                parsed.config.games = match value.parse() {
+                    Ok(games) if games > 0 => games,
+                    _ => return Err(format!("invalid game count: {value}")),
+                };
+            }
+            "--bye-points" => {
+                let value = value("--bye-points")?;
+                parsed.bye_points = match value.parse::<f64>() {
+                    Ok(points) if points.is_finite() && points >= 0.0 => Some(points),
+                    _ => return Err(format!("invalid bye points: {value}")),
+                };
+            }
+            "--seed" => {
+                let value = value("--seed")?;
+                parsed.config.seed = value.parse().map_err(|_| format!("invalid seed: {value}"))?;
+            }
+            "--threads" => {
+                let value = value("--threads")?;
+                parsed.config.threads = match value.parse() {
+                    Ok(threads) if threads > 0 => threads,
+                    _ => return Err(format!("invalid thread count: {value}")),
+                };
+            }
+            "--json" => parsed.json = Some(PathBuf::from(value("--json")?)),
+            _ => return Err(format!("unknown argument: {arg}")),
+        }
+    }
+    if parsed.config.entrants.len() < 2 {
+        return Err(String::from("a tournament needs at least two entrants"));
+    }
+    // numbered so readers of the leaderboard can tell namesakes apart
+    let entrants = &mut parsed.config.entrants;
+    for i in 1..entrants.len() {
+        let taken = entrants[..i].iter().filter(|e| e.name == entrants[i].name).count();
+        if taken > 0 {
+            entrants[i].name = format!("{} {}", entrants[i].name, taken + 1);
+        }
+    }
+    parsed.config.rounds = parsed
+        .rounds
+        .unwrap_or_else(|| TournamentConfig::default_rounds(parsed.config.entrants.len()));
+    // a bye is as good as winning the match unless said otherwise
+    parsed.config.bye_points = parsed.bye_points.unwrap_or(parsed.config.games as f64);
+    Ok(parsed)
+}
+
+fn main() {
+    let args = parse_args(env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{err}\n{USAGE}");
+        process::exit(2);
+    });
+
+    let solver = args.config.needs_solver().then(|| {
+        eprintln!("Loading the strategy table...");
+        Arc::new(Solver::load_or_build())
+    });
+    let leaderboard = tournament::run(&args.config, solver);
+
+    print!("{leaderboard}");
+    if let Some(path) = &args.json {
+        let json = serde_json::to_string_pretty(&leaderboard).expect("leaderboards always serialize");
+        if let Err(err) = fs::write(path, json) {
+            eprintln!("cannot write {}: {err}", path.display());
+            process::exit(1);
+        }
+    }
+}
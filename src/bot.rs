@@ -0,0 +1,446 @@
+pub mod external;
+
+use std::{fmt, str::FromStr, sync::Arc};
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::{Game, GameError, mode::Mode, roller::DiceRoller},
+    model::{
+        die::DieFace,
+        player::Player,
+        roll::{AllRolls, RollType},
+        ruleset::Ruleset,
+    },
+    score_util::{calc_score, can_score},
+    solver::{Solver, SolverState},
+};
+
+/// Average score of each category under optimal play, which the heuristic
+/// player measures its options against.
+const PAR: [f32; 13] = [
+    2.1, 5.3, 8.6, 12.2, 15.7, 19.2, 21.7, 13.1, 22.6, 29.5, 32.7, 22.0, 16.9,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    /// Makes any legal move at random.
+    Random,
+    /// Scores the highest category for its first roll.
+    Greedy,
+    /// Chases sets and straights with simple rules of thumb.
+    Heuristic,
+    /// Maximises its expected final score.
+    Optimal,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Random,
+        Difficulty::Greedy,
+        Difficulty::Heuristic,
+        Difficulty::Optimal,
+    ];
+
+    pub fn needs_solver(self) -> bool {
+        self == Difficulty::Optimal
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Difficulty::Random => "Random",
+            Difficulty::Greedy => "Greedy",
+            Difficulty::Heuristic => "Heuristic",
+            Difficulty::Optimal => "Optimal",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Difficulty, String> {
+        Difficulty::ALL
+            .into_iter()
+            .find(|d| d.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown difficulty: {s}"))
+    }
+}
+
+/// What a bot does with the dice it has just rolled.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Decision {
+    /// Reroll every die that is not held.
+    Reroll(Vec<bool>),
+    /// Score a category in a scorecard column.
+    Score(usize, RollType),
+}
+
+/// A computer player.
+pub struct Bot {
+    pub difficulty: Difficulty,
+    rng: StdRng,
+    solver: Option<Arc<Solver>>,
+    mode: Mode,
+}
+
+impl Bot {
+    /// `solver` is required for the optimal player and ignored by the others.
+    pub fn new(difficulty: Difficulty, seed: u64, solver: Option<Arc<Solver>>) -> Bot {
+        assert!(
+            solver.is_some() || !difficulty.needs_solver(),
+            "the {difficulty} bot needs a solver"
+        );
+        Bot {
+            difficulty,
+            rng: StdRng::seed_from_u64(seed),
+            solver,
+            mode: Mode::Standard,
+        }
+    }
+
+    /// Plays by the rules of `mode`.
+    pub fn with_mode(mut self, mode: Mode) -> Bot {
+        self.mode = mode;
+        self
+    }
+
+    /// Picks a move for `dice`, rolled with `rolls_left` rerolls to go, on
+    /// a scorecard of weighted `columns`, or none if there is nowhere left
+    /// to score.
+    pub fn decide(&mut self, columns: &[AllRolls], dice: &[u8], rolls_left: u8) -> Option<Decision> {
+        let faces: Vec<DieFace> = dice.iter().map(|v| DieFace::new(*v)).collect();
+        let ordered = self.mode == Mode::Ordered;
+        let mut legal: Vec<Move> = columns
+            .iter()
+            .enumerate()
+            .flat_map(|(column, rolls)| {
+                let faces = &faces;
+                rolls
+                    .iter()
+                    .filter(move |r| r.score.is_none() && (ordered || can_score(r.roll_type, rolls, faces)))
+                    .map(move |r| Move {
+                        column,
+                        roll_type: r.roll_type,
+                        score: calc_score(*r, rolls, faces),
+                    })
+            })
+            .collect();
+        if ordered {
+            // only the first open category may be scored
+            legal.truncate(1);
+        }
+        if legal.is_empty() {
+            return None;
+        }
+
+        match self.difficulty {
+            Difficulty::Random => {
+                if rolls_left > 0 && self.rng.random_bool(0.5) {
+                    Some(Decision::Reroll((0..dice.len()).map(|_| self.rng.random_bool(0.5)).collect()))
+                } else {
+                    Some(legal[self.rng.random_range(0..legal.len())].decision())
+                }
+            }
+            Difficulty::Greedy => {
+                let best = legal.iter().rev().max_by_key(|m| m.weighted_score())?;
+                Some(best.decision())
+            }
+            Difficulty::Heuristic => heuristic(&legal, dice, rolls_left, columns[0].ruleset),
+            // the strategy table only knows the standard rules with a free
+            // choice of category
+            Difficulty::Optimal if ordered || !columns[0].has_strategy_table() => {
+                heuristic(&legal, dice, rolls_left, columns[0].ruleset)
+            }
+            Difficulty::Optimal => {
+                let solver = self.solver.as_ref().unwrap();
+                // each column is valued as a game of its own, and the move
+                // adding the most weighted expected score wins
+                let mut best: Option<(f32, Decision)> = None;
+                for (column, rolls) in columns.iter().enumerate().filter(|(_, r)| !r.is_complete()) {
+                    let weight = Player::weight(column) as f32;
+                    let state = SolverState::from_rolls(rolls);
+                    let base = solver.expected_value(state);
+                    let mut options = Vec::new();
+                    if rolls_left > 0 {
+                        let (hold, value) = solver.best_hold(state, dice, rolls_left);
+                        if !hold.iter().all(|h| *h) {
+                            options.push((value, Decision::Reroll(hold)));
+                        }
+                    }
+                    if let Some((roll_type, value)) = solver.best_category(state, dice) {
+                        options.push((value, Decision::Score(column, roll_type)));
+                    }
+                    for (value, decision) in options {
+                        let gain = weight * (value - base);
+                        if best.as_ref().is_none_or(|(best, _)| gain > *best) {
+                            best = Some((gain, decision));
+                        }
+                    }
+                }
+                best.map(|(_, decision)| decision)
+            }
+        }
+    }
+
+    /// Plays the current player's whole turn without any animation.
+    pub fn play_turn(&mut self, game: &mut Game, roller: &mut dyn DiceRoller) -> Result<u32, GameError> {
+        game.roll(roller)?;
+        loop {
+            let dice: Vec<u8> = game.dice().iter().map(|d| d.value).collect();
+            let rolls_left = game.rolls_left();
+            let Some(decision) = self.decide(&game.current_player().columns, &dice, rolls_left) else {
+                // nowhere to score, so the player can only drop out
+                game.forfeit()?;
+                return Ok(0);
+            };
+            match decision {
+                Decision::Reroll(hold) => {
+                    for (i, held) in hold.into_iter().enumerate() {
+                        if game.dice()[i].held != held {
+                            game.hold(i)?;
+                        }
+                    }
+                    game.roll(roller)?;
+                }
+                Decision::Score(column, roll_type) => return game.score_in(column, roll_type),
+            }
+        }
+    }
+}
+
+/// A legal place to score the dice.
+struct Move {
+    column: usize,
+    roll_type: RollType,
+    score: u32,
+}
+
+impl Move {
+    fn weighted_score(&self) -> u32 {
+        Player::weight(self.column) * self.score
+    }
+
+    /// How far the move beats the category's par, in weighted points.
+    fn over_par(&self) -> f32 {
+        Player::weight(self.column) as f32 * (self.score as f32 - par(self.roll_type))
+    }
+
+    fn decision(&self) -> Decision {
+        Decision::Score(self.column, self.roll_type)
+    }
+}
+
+/// What `roll_type` scores on average, to judge a score against.
+pub(crate) fn par(roll_type: RollType) -> f32 {
+    // the Maxi categories are rough guesses rather than solved values
+    match roll_type {
+        RollType::OnePair => 10.5,
+        RollType::TwoPairs => 17.0,
+        RollType::ThreePairs => 13.0,
+        RollType::Castle => 13.0,
+        RollType::Tower => 11.0,
+        RollType::FullStraight => 8.0,
+        _ => PAR[RollType::STANDARD.iter().position(|r| *r == roll_type).unwrap()],
+    }
+}
+
+/// Keeps made hands, holds four to a straight and otherwise goes for the
+/// most common face; scores wherever it beats par by the most. None if
+/// there is no `legal` move.
+fn heuristic(legal: &[Move], dice: &[u8], rolls_left: u8, ruleset: Ruleset) -> Option<Decision> {
+    let best = legal
+        .iter()
+        .max_by(|a, b| a.over_par().total_cmp(&b.over_par()))?;
+    if rolls_left == 0 {
+        return Some(best.decision());
+    }
+
+    let is_open = |roll_type| legal.iter().any(|m| m.roll_type == roll_type);
+    // hands scoring the dice showing, such as a Yacht full house, are not made hands
+    let made = [
+        RollType::Yahtzee,
+        RollType::LargeStraight,
+        RollType::FullHouse,
+        RollType::SmallStraight,
+    ]
+    .into_iter()
+    .filter(|roll_type| *roll_type != RollType::SmallStraight || !is_open(RollType::LargeStraight))
+    .filter_map(|roll_type| match roll_type {
+        RollType::Yahtzee => Some((roll_type, ruleset.yahtzee_points())),
+        _ => ruleset.fixed_points(roll_type).map(|score| (roll_type, score)),
+    })
+    .find_map(|(roll_type, score)| {
+        // made hands go in the column worth the most
+        legal
+            .iter()
+            .filter(|m| m.roll_type == roll_type && m.score == score)
+            .max_by_key(|m| m.column)
+    });
+    if let Some(made) = made {
+        return Some(made.decision());
+    }
+
+    let mut counts = [0usize; 7];
+    for value in dice {
+        counts[*value as usize] += 1;
+    }
+    let mut keep: Vec<u8> = Vec::new();
+    if is_open(RollType::SmallStraight) || is_open(RollType::LargeStraight) {
+        let mut run: Vec<u8> = Vec::new();
+        for value in 1..=6u8 {
+            if counts[value as usize] > 0 {
+                run.push(value);
+                if run.len() > keep.len() {
+                    keep = run.clone();
+                }
+            } else {
+                run.clear();
+            }
+        }
+        if keep.len() < 4 {
+            keep.clear();
+        }
+    }
+    if keep.is_empty() {
+        // sets only help in their upper category or the of-a-kind ones
+        let sets_open = [RollType::ThreeOfAKind, RollType::FourOfAKind, RollType::Yahtzee]
+            .into_iter()
+            .any(is_open);
+        let value = (1..=6u8)
+            .max_by_key(|v| {
+                let useful = sets_open || is_open(RollType::STANDARD[*v as usize - 1]);
+                (useful, counts[*v as usize], *v)
+            })
+            .unwrap();
+        keep = vec![value; counts[value as usize]];
+    }
+
+    let mut hold = vec![false; dice.len()];
+    for value in keep {
+        if let Some(i) = (0..dice.len()).find(|i| !hold[*i] && dice[*i] == value) {
+            hold[i] = true;
+        }
+    }
+    Some(Decision::Reroll(hold))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::roller::SeededRoller;
+
+    #[test]
+    fn test_bots_finish_games() {
+        for difficulty in [Difficulty::Random, Difficulty::Greedy, Difficulty::Heuristic] {
+            for ruleset in [Ruleset::Yacht, Ruleset::Maxi] {
+                let mut game = Game::new().with_ruleset(ruleset);
+                let mut bot = Bot::new(difficulty, 7, None);
+                let mut roller = SeededRoller::new(7);
+                while !game.is_game_over() {
+                    bot.play_turn(&mut game, &mut roller).unwrap();
+                }
+            }
+            let mut game = Game::new();
+            let mut bot = Bot::new(difficulty, 7, None);
+            let mut roller = SeededRoller::new(7);
+            while !game.is_game_over() {
+                bot.play_turn(&mut game, &mut roller).unwrap();
+            }
+            assert!(game.total_score() > 0, "{difficulty} scored nothing");
+        }
+    }
+
+    #[test]
+    fn test_greedy_takes_highest_score() {
+        let mut bot = Bot::new(Difficulty::Greedy, 0, None);
+        let rolls = [AllRolls::new()];
+        assert_eq!(
+            bot.decide(&rolls, &[2, 3, 4, 5, 6], 2),
+            Some(Decision::Score(0, RollType::LargeStraight))
+        );
+    }
+
+    #[test]
+    fn test_no_decision_without_an_open_box() {
+        let mut rolls = AllRolls::new();
+        for roll_type in RollType::STANDARD {
+            rolls.roll_mut(roll_type).score = Some(0);
+        }
+        for difficulty in [Difficulty::Random, Difficulty::Greedy, Difficulty::Heuristic] {
+            let mut bot = Bot::new(difficulty, 0, None);
+            assert_eq!(bot.decide(&[rolls.clone()], &[1, 2, 3, 4, 5], 0), None);
+        }
+    }
+
+    #[test]
+    fn test_heuristic_holds() {
+        let mut bot = Bot::new(Difficulty::Heuristic, 0, None);
+        let rolls = [AllRolls::new()];
+        assert_eq!(
+            bot.decide(&rolls, &[1, 2, 3, 4, 6], 2),
+            Some(Decision::Reroll(vec![true, true, true, true, false]))
+        );
+        assert_eq!(
+            bot.decide(&rolls, &[5, 2, 5, 1, 6], 2),
+            Some(Decision::Reroll(vec![true, false, true, false, false]))
+        );
+        assert_eq!(
+            bot.decide(&rolls, &[3, 3, 4, 4, 3], 2),
+            Some(Decision::Score(0, RollType::FullHouse))
+        );
+        // a made Yacht straight is worth 30, not the 40 of a Yahtzee one
+        let rolls = [AllRolls::with_ruleset(Ruleset::Yacht)];
+        assert_eq!(
+            bot.decide(&rolls, &[2, 3, 4, 5, 6], 2),
+            Some(Decision::Score(0, RollType::LargeStraight))
+        );
+    }
+
+    #[test]
+    fn test_bots_use_weighted_columns() {
+        let mut columns = vec![AllRolls::new(); 3];
+        columns[2].roll_mut(RollType::FullHouse).score = Some(25);
+        let mut bot = Bot::new(Difficulty::Heuristic, 0, None);
+        assert_eq!(
+            bot.decide(&columns, &[3, 3, 4, 4, 3], 2),
+            Some(Decision::Score(1, RollType::FullHouse))
+        );
+        let mut bot = Bot::new(Difficulty::Greedy, 0, None);
+        assert_eq!(
+            bot.decide(&columns, &[1, 1, 1, 2, 2], 2),
+            Some(Decision::Score(1, RollType::FullHouse))
+        );
+
+        let mut game = Game::new().with_mode(crate::game::mode::Mode::Triple);
+        let mut roller = SeededRoller::new(3);
+        while !game.is_game_over() {
+            bot.play_turn(&mut game, &mut roller).unwrap();
+        }
+        assert!(game.current_player().columns.iter().all(AllRolls::is_complete));
+    }
+
+    #[test]
+    fn test_bots_play_ordered_games() {
+        let mut game = Game::new().with_mode(Mode::Ordered);
+        let mut bot = Bot::new(Difficulty::Greedy, 3, None).with_mode(Mode::Ordered);
+        let mut roller = SeededRoller::new(3);
+        for roll_type in RollType::STANDARD {
+            bot.play_turn(&mut game, &mut roller).unwrap();
+            assert!(game.rolls().roll(roll_type).score.is_some());
+        }
+        assert!(game.is_game_over());
+    }
+
+    #[test]
+    fn test_difficulty_from_str() {
+        assert_eq!("optimal".parse(), Ok(Difficulty::Optimal));
+        assert_eq!(" Greedy".parse(), Ok(Difficulty::Greedy));
+        assert!("expert".parse::<Difficulty>().is_err());
+    }
+}
@@ -0,0 +1,364 @@
+//! Players run by another program. Whenever the player has to decide, the
+//! program gets the game as one line of JSON on its stdin and answers with
+//! one line on its stdout, either
+//!
+//! ```json
+//! {"type": "hold", "held": [true, true, false, false, true]}
+//! {"type": "score", "category": "FullHouse", "column": 0}
+//! ```
+//!
+//! where a hold rerolls the dice that are not held and `column` may be left
+//! out outside Triple games. A program that answers late, answers nonsense,
+//! makes an illegal move or exits forfeits the game.
+
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    process::{Child, Command, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bot::Decision,
+    game::{Game, GameError, mode::Mode, roller::DiceRoller},
+    model::{roll::RollType, ruleset::Ruleset},
+};
+
+/// How long a program may think about a move.
+pub const MOVE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The game as the program to move sees it.
+#[derive(Debug, Serialize)]
+struct State {
+    /// The program's own seat in `players`.
+    seat: usize,
+    mode: Mode,
+    ruleset: Ruleset,
+    dice: Vec<u8>,
+    held: Vec<bool>,
+    rolls_left: u8,
+    /// Where the dice may be scored, and what they would score there.
+    open: Vec<Open>,
+    players: Vec<Scorecard>,
+}
+
+#[derive(Debug, Serialize)]
+struct Open {
+    column: usize,
+    category: RollType,
+    points: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct Scorecard {
+    name: String,
+    total: u32,
+    forfeited: bool,
+    /// The categories filled so far, column by column.
+    columns: Vec<Vec<Scored>>,
+}
+
+#[derive(Debug, Serialize)]
+struct Scored {
+    category: RollType,
+    points: u32,
+}
+
+impl State {
+    fn of(game: &Game) -> State {
+        let player = game.current_player();
+        State {
+            seat: game.current_player_index(),
+            mode: game.mode(),
+            ruleset: game.ruleset(),
+            dice: game.dice().iter().map(|d| d.value).collect(),
+            held: game.dice().iter().map(|d| d.held).collect(),
+            rolls_left: game.rolls_left(),
+            open: player
+                .columns
+                .iter()
+                .enumerate()
+                .flat_map(|(column, rolls)| rolls.iter().map(move |r| (column, r.roll_type)))
+                .filter_map(|(column, category)| {
+                    let points = game.check_score(column, category).ok()?;
+                    Some(Open { column, category, points })
+                })
+                .collect(),
+            players: game
+                .players()
+                .iter()
+                .map(|p| Scorecard {
+                    name: p.name.clone(),
+                    total: p.total_score(),
+                    forfeited: p.forfeited,
+                    columns: p
+                        .columns
+                        .iter()
+                        .map(|rolls| {
+                            rolls
+                                .iter()
+                                .filter_map(|r| Some(Scored { category: r.roll_type, points: r.score? }))
+                                .collect()
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+enum Answer {
+    Hold {
+        held: Vec<bool>,
+    },
+    Score {
+        category: RollType,
+        #[serde(default)]
+        column: usize,
+    },
+}
+
+/// A running program playing one seat.
+pub struct ExternalBot {
+    child: Child,
+    /// Questions for the program, written to its stdin on their own thread.
+    questions: Sender<String>,
+    lines: Receiver<String>,
+    timeout: Duration,
+    /// When the answer to the last question is due.
+    deadline: Option<Instant>,
+}
+
+impl ExternalBot {
+    /// Starts `command` with the system shell. Its stderr is discarded so
+    /// it cannot draw over the game.
+    pub fn spawn(command: &str) -> io::Result<ExternalBot> {
+        let mut child = shell(command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let mut stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+        let (questions, asked) = mpsc::channel::<String>();
+        thread::spawn(move || {
+            // a program that stops reading only holds up this thread, and
+            // runs out of time to answer
+            for question in asked {
+                if stdin.write_all(question.as_bytes()).and_then(|()| stdin.flush()).is_err() {
+                    break;
+                }
+            }
+        });
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if !line.trim().is_empty() && sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(ExternalBot {
+            child,
+            questions,
+            lines,
+            timeout: MOVE_TIMEOUT,
+            deadline: None,
+        })
+    }
+
+    /// Gives the program `timeout` per move instead of `MOVE_TIMEOUT`.
+    pub fn with_timeout(mut self, timeout: Duration) -> ExternalBot {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Whether the program has been asked for a move it has not made yet.
+    pub fn is_thinking(&self) -> bool {
+        self.deadline.is_some()
+    }
+
+    /// Sends the program the game, to answer with its next move within the
+    /// timeout, which runs from now even while the game is still being
+    /// written. The error is the reason the program forfeits.
+    pub fn ask(&mut self, game: &Game) -> Result<(), String> {
+        let mut json = serde_json::to_string(&State::of(game)).expect("game states always serialize");
+        json.push('\n');
+        self.questions
+            .send(json)
+            .map_err(|_| String::from("stopped reading the game"))?;
+        self.deadline = Some(Instant::now() + self.timeout);
+        Ok(())
+    }
+
+    /// The program's answer to the last question, if it is in or overdue.
+    pub fn try_answer(&mut self, game: &Game) -> Option<Result<Decision, String>> {
+        let deadline = self.deadline?;
+        let line = match self.lines.try_recv() {
+            Ok(line) => Ok(line),
+            Err(TryRecvError::Empty) if Instant::now() < deadline => return None,
+            Err(TryRecvError::Empty) => Err(self.timed_out()),
+            Err(TryRecvError::Disconnected) => Err(String::from("exited")),
+        };
+        self.deadline = None;
+        Some(line.and_then(|line| check(game, &line)))
+    }
+
+    /// Asks for a move and waits for it.
+    pub fn decide(&mut self, game: &Game) -> Result<Decision, String> {
+        self.ask(game)?;
+        let line = match self.lines.recv_timeout(self.timeout) {
+            Ok(line) => Ok(line),
+            Err(RecvTimeoutError::Timeout) => Err(self.timed_out()),
+            Err(RecvTimeoutError::Disconnected) => Err(String::from("exited")),
+        };
+        self.deadline = None;
+        check(game, &line?)
+    }
+
+    /// Plays the current player's whole turn, like `Bot::play_turn`. The
+    /// error is the reason the program forfeits, which is left to the caller.
+    pub fn play_turn(&mut self, game: &mut Game, roller: &mut dyn DiceRoller) -> Result<u32, String> {
+        game.roll(roller).map_err(|err| err.to_string())?;
+        loop {
+            match self.decide(game)? {
+                Decision::Reroll(hold) => {
+                    for (i, held) in hold.into_iter().enumerate() {
+                        if game.dice()[i].held != held {
+                            game.hold(i).map_err(|err| err.to_string())?;
+                        }
+                    }
+                    game.roll(roller).map_err(|err| err.to_string())?;
+                }
+                Decision::Score(column, roll_type) => {
+                    return game.score_in(column, roll_type).map_err(|err| err.to_string());
+                }
+            }
+        }
+    }
+
+    fn timed_out(&self) -> String {
+        format!("took longer than {:?}", self.timeout)
+    }
+}
+
+impl Drop for ExternalBot {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// A short name for the program `command` runs, such as "greedy" for
+/// `python3 bots/greedy.py --fast`.
+pub fn program_name(command: &str) -> String {
+    command
+        .split_whitespace()
+        .rev()
+        .find(|word| !word.starts_with('-'))
+        .map(|word| word.rsplit(['/', '\\']).next().unwrap_or(word))
+        .map(|file| file.split_once('.').map_or(file, |(stem, _)| stem))
+        .filter(|name| !name.is_empty())
+        .unwrap_or("Program")
+        .to_string()
+}
+
+/// Turns an answer into a move, as long as it is a legal one.
+fn check(game: &Game, line: &str) -> Result<Decision, String> {
+    let answer: Answer = serde_json::from_str(line).map_err(|err| format!("sent an invalid answer: {err}"))?;
+    match answer {
+        Answer::Hold { held } => {
+            if game.rolls_left() == 0 {
+                return Err(GameError::NoRollsLeft.to_string());
+            }
+            if held.len() != game.dice().len() {
+                return Err(format!("held {} dice out of {}", held.len(), game.dice().len()));
+            }
+            Ok(Decision::Reroll(held))
+        }
+        Answer::Score { category, column } => {
+            game.check_score(column, category).map_err(|err| err.to_string())?;
+            Ok(Decision::Score(column, category))
+        }
+    }
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::game::roller::ScriptedRoller;
+
+    #[test]
+    fn test_program_plays_until_it_breaks_the_rules() {
+        // holds everything once, then always scores Chance
+        let script = r#"read line; echo '{"type":"hold","held":[true,true,true,true,true]}'
+            while read line; do echo '{"type":"score","category":"Chance"}'; done"#;
+        let mut bot = ExternalBot::spawn(script).unwrap();
+        let mut game = Game::new();
+        let mut dice = ScriptedRoller::new([3; 10]);
+        assert_eq!(bot.play_turn(&mut game, &mut dice), Ok(15));
+        assert_eq!(game.roll_count(), 0);
+        assert_eq!(game.rolls().roll(RollType::Chance).score, Some(15));
+        assert_eq!(bot.play_turn(&mut game, &mut dice), Err(String::from("Chance is already scored")));
+    }
+
+    #[test]
+    fn test_silence_and_nonsense_forfeit() {
+        let mut game = Game::new();
+        game.roll(&mut ScriptedRoller::new([1; 5])).unwrap();
+        let mut bot = ExternalBot::spawn("sleep 5").unwrap().with_timeout(Duration::from_millis(50));
+        assert_eq!(bot.decide(&game), Err(String::from("took longer than 50ms")));
+
+        let mut bot = ExternalBot::spawn("read line; echo roll").unwrap();
+        bot.ask(&game).unwrap();
+        let answer = loop {
+            if let Some(answer) = bot.try_answer(&game) {
+                break answer;
+            }
+            thread::sleep(Duration::from_millis(10));
+        };
+        assert!(answer.unwrap_err().starts_with("sent an invalid answer"));
+        assert!(bot.decide(&game).is_err());
+    }
+
+    #[test]
+    fn test_program_not_reading_runs_out_of_time() {
+        let mut game = Game::new();
+        game.roll(&mut ScriptedRoller::new([1; 5])).unwrap();
+        let mut bot = ExternalBot::spawn("sleep 5").unwrap().with_timeout(Duration::from_millis(50));
+        let started = Instant::now();
+        // more than a pipe holds, which would block writing straight to it
+        for _ in 0..200 {
+            bot.ask(&game).unwrap();
+        }
+        assert_eq!(bot.decide(&game), Err(String::from("took longer than 50ms")));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_program_name() {
+        assert_eq!(program_name("python3 bots/greedy.py --fast"), "greedy");
+        assert_eq!(program_name("./mybot"), "mybot");
+        assert_eq!(program_name(""), "Program");
+    }
+}
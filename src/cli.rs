@@ -0,0 +1,107 @@
+use std::{env, path::PathBuf};
+
+use crate::{
+    bot::Difficulty,
+    game::mode::Mode,
+    model::{player::MAX_PLAYERS, ruleset::Ruleset},
+};
+
+pub const USAGE: &str =
+    "Usage: rahtzee [--seed N] [--players NAME,NAME,...] [--bots LEVEL,LEVEL,...] [--bot-cmd PROGRAM]... [--mode MODE] [--ruleset RULES] [--rules FILE] [--one-undo-per-turn] [--daily] [--host PORT | --join ADDR | --watch ADDR] [--replay FILE]";
+
+#[derive(Default)]
+pub struct Args {
+    /// Seed for the dice, so a game can be replayed.
+    pub seed: Option<u64>,
+    /// Names for a hot-seat game, in turn order.
+    pub players: Vec<String>,
+    /// Computer players seated after the humans.
+    pub bots: Vec<Difficulty>,
+    /// Shell commands of external programs seated after the bots.
+    pub bot_cmds: Vec<String>,
+    pub mode: Mode,
+    pub ruleset: Ruleset,
+    /// House rules to read instead of the config directory's `rules.toml`.
+    pub rules: Option<PathBuf>,
+    /// Allows each turn to be undone only once.
+    pub one_undo_per_turn: bool,
+    /// Plays today's challenge, rolled with the same dice for everyone.
+    pub daily: bool,
+    /// Port to host a network game on.
+    pub host: Option<u16>,
+    /// Address of a network game to join.
+    pub join: Option<String>,
+    /// Address of a network game to watch.
+    pub watch: Option<String>,
+    /// A recorded game to watch instead of playing.
+    pub replay: Option<PathBuf>,
+}
+
+impl Args {
+    pub fn parse() -> Result<Args, String> {
+        Args::parse_from(env::args().skip(1))
+    }
+
+    pub fn parse_from(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => {
+                    let value = args.next().ok_or("--seed needs a value")?;
+                    let seed = value
+                        .parse()
+                        .map_err(|_| format!("invalid seed: {value}"))?;
+                    parsed.seed = Some(seed);
+                }
+                "--players" => {
+                    let value = args.next().ok_or("--players needs a value")?;
+                    let names: Vec<String> = value
+                        .split(',')
+                        .map(|name| name.trim().to_string())
+                        .filter(|name| !name.is_empty())
+                        .collect();
+                    if names.is_empty() || names.len() > MAX_PLAYERS {
+                        return Err(format!("--players takes 1 to {MAX_PLAYERS} names"));
+                    }
+                    parsed.players = names;
+                }
+                "--bots" => {
+                    let value = args.next().ok_or("--bots needs a value")?;
+                    parsed.bots = value
+                        .split(',')
+                        .map(str::parse)
+                        .collect::<Result<_, _>>()?;
+                }
+                "--bot-cmd" => parsed.bot_cmds.push(args.next().ok_or("--bot-cmd needs a program")?),
+                "--mode" => parsed.mode = args.next().ok_or("--mode needs a value")?.parse()?,
+                "--ruleset" => parsed.ruleset = args.next().ok_or("--ruleset needs a value")?.parse()?,
+                "--rules" => {
+                    let value = args.next().ok_or("--rules needs a value")?;
+                    parsed.rules = Some(PathBuf::from(value));
+                }
+                "--one-undo-per-turn" => parsed.one_undo_per_turn = true,
+                "--daily" => parsed.daily = true,
+                "--host" => {
+                    let value = args.next().ok_or("--host needs a port")?;
+                    let port = value.parse().map_err(|_| format!("invalid port: {value}"))?;
+                    parsed.host = Some(port);
+                }
+                "--join" => parsed.join = Some(args.next().ok_or("--join needs an address")?),
+                "--watch" => parsed.watch = Some(args.next().ok_or("--watch needs an address")?),
+                "--replay" => {
+                    let value = args.next().ok_or("--replay needs a value")?;
+                    parsed.replay = Some(PathBuf::from(value));
+                }
+                _ => return Err(format!("unknown argument: {arg}")),
+            }
+        }
+        if [parsed.host.is_some(), parsed.join.is_some(), parsed.watch.is_some()].iter().filter(|set| **set).count() > 1 {
+            return Err(String::from("only one of --host, --join and --watch can be given"));
+        }
+        if parsed.players.len().max(1) + parsed.bots.len() + parsed.bot_cmds.len() > MAX_PLAYERS {
+            return Err(format!("a game takes at most {MAX_PLAYERS} players"));
+        }
+        Ok(parsed)
+    }
+}
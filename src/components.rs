@@ -1,4 +1,9 @@
 pub mod die;
 pub mod dice;
+pub mod high_score_table;
+pub mod hint_panel;
+pub mod menu;
 pub mod roll_slot;
 pub mod roll_slots;
+pub mod standings;
+pub mod stats_panel;
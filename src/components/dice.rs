@@ -1,17 +1,32 @@
 use ratatui::{buffer::Buffer, layout::{Constraint, Direction, Layout, Rect}, widgets::Widget};
 
-use crate::{app::DieFace, components::die::Die};
+use crate::{components::die::Die, model::die::DieFace};
+
+const DIE_WIDTH: u16 = 9;
 
 pub struct Dice {
     pub faces: Vec<DieFace>,
+    /// Dice the hint suggests holding, if a hint is shown.
+    pub suggested: Vec<bool>,
 }
 
 impl Dice {
     pub fn new(faces: Vec<DieFace>) -> Self {
         Self {
             faces,
+            suggested: Vec::new(),
         }
     }
+
+    /// Width of a row of `count` dice.
+    pub fn width(count: usize) -> u16 {
+        count as u16 * DIE_WIDTH
+    }
+
+    pub fn suggested(mut self, suggested: Vec<bool>) -> Self {
+        self.suggested = suggested;
+        self
+    }
 }
 
 impl Widget for Dice {
@@ -19,7 +34,7 @@ impl Widget for Dice {
         // Create constraints based on number of dice
         let constraints: Vec<Constraint> = self.faces
             .iter()
-            .map(|_| Constraint::Length(9))
+            .map(|_| Constraint::Length(DIE_WIDTH))
             .collect();
         
         // Render die faces horizontally
@@ -29,8 +44,9 @@ impl Widget for Dice {
             // .spacing(1)
             .split(area);
         
-        for (face, area) in self.faces.iter().zip(dice_row.iter()) {
-            let die = Die::new(*face);
+        for (i, (face, area)) in self.faces.iter().zip(dice_row.iter()).enumerate() {
+            let suggested = self.suggested.get(i).copied().unwrap_or(false);
+            let die = Die::new(*face).suggested(suggested);
             die.render(*area, buf);
         }
     }
@@ -7,15 +7,25 @@ use ratatui::{
     widgets::{Block, Paragraph, Widget},
 };
 
-use crate::{app::DieFace, theme::Theme};
+use crate::{model::die::DieFace, theme::Theme};
 
 pub struct Die {
     pub face: DieFace,
+    /// Marks a die the hint suggests holding.
+    pub suggested: bool,
 }
 
 impl Die {
     pub fn new(face: DieFace) -> Self {
-        Self { face }
+        Self {
+            face,
+            suggested: false,
+        }
+    }
+
+    pub fn suggested(mut self, suggested: bool) -> Self {
+        self.suggested = suggested;
+        self
     }
 }
 
@@ -46,9 +56,12 @@ impl Widget for Die {
         };
         let lines: Vec<Line> = die_face.iter().map(|&s| Line::from(s).fg(color)).collect();
 
-        let block = Block::bordered()
+        let mut block = Block::bordered()
             .border_set(border::ROUNDED)
             .fg(border_color);
+        if self.suggested {
+            block = block.title_bottom(Line::from("hold".fg(Theme::SECONDARY).bold()).centered());
+        }
 
         Paragraph::new(lines).block(block).render(area, buf);
     }
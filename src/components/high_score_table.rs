@@ -0,0 +1,66 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Row, Table, Widget},
+};
+
+use crate::{high_scores::HighScore, theme::Theme};
+
+pub struct HighScoreTable<'a> {
+    pub entries: &'a [HighScore],
+    /// Row to highlight, such as a score that was just added.
+    pub highlight: Option<usize>,
+}
+
+impl<'a> HighScoreTable<'a> {
+    pub fn new(entries: &'a [HighScore], highlight: Option<usize>) -> HighScoreTable<'a> {
+        HighScoreTable { entries, highlight }
+    }
+}
+
+impl Widget for HighScoreTable<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if self.entries.is_empty() {
+            Line::from("No high scores yet".fg(Theme::TEXT_DIM))
+                .centered()
+                .render(area, buf);
+            return;
+        }
+
+        let header = Row::new(["#", "Name", "Date", "Score", "Bonus", "★"])
+            .style(Style::new().fg(Theme::SECONDARY).bold());
+
+        let rows = self.entries.iter().enumerate().map(|(i, entry)| {
+            let color = if self.highlight == Some(i) {
+                Theme::ACCENT
+            } else {
+                Theme::TEXT
+            };
+            Row::new([
+                format!("{}", i + 1),
+                entry.name.clone(),
+                entry.date.format("%Y-%m-%d").to_string(),
+                entry.total.to_string(),
+                entry.upper_bonus.to_string(),
+                entry.yahtzee_bonus_count.to_string(),
+            ])
+            .style(Style::new().fg(color))
+        });
+
+        Table::new(
+            rows,
+            [
+                Constraint::Length(2),
+                Constraint::Min(10),
+                Constraint::Length(10),
+                Constraint::Length(5),
+                Constraint::Length(5),
+                Constraint::Length(2),
+            ],
+        )
+        .header(header)
+        .render(area, buf);
+    }
+}
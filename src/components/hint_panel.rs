@@ -0,0 +1,74 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    text::Line,
+    widgets::{Paragraph, Widget},
+};
+
+use crate::{
+    components::standings::short_label,
+    hint::Hint,
+    keymap::{Action, Keymap},
+    model::ruleset::Ruleset,
+    theme::Theme,
+};
+
+/// The solver's advice, shown beside the dice in place of the roll counter.
+pub struct HintPanel<'a> {
+    /// `None` while the strategy table is still loading.
+    pub hint: Option<&'a Hint>,
+    pub score: u32,
+    /// Names the dice to hold by their keys.
+    pub keymap: &'a Keymap,
+}
+
+impl Widget for HintPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut lines = vec![Line::from("HINT".fg(Theme::ACCENT).bold())];
+        match self.hint {
+            None => lines.push(Line::from("Thinking…".fg(Theme::TEXT_DIM))),
+            Some(hint) => {
+                if let Some(hold) = &hint.hold {
+                    let keys: Vec<String> = hold
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, held)| **held)
+                        .map(|(i, _)| self.keymap.label(Action::Hold(i)).unwrap_or_else(|| (i + 1).to_string()))
+                        .collect();
+                    // single characters run together, longer key names need spaces
+                    let separator = if keys.iter().all(|key| key.chars().count() == 1) { "" } else { " " };
+                    let dice = keys.join(separator);
+                    lines.push(match dice.as_str() {
+                        "" => Line::from("Reroll all".fg(Theme::SECONDARY).bold()),
+                        dice => Line::from(vec![
+                            "Hold ".fg(Theme::TEXT),
+                            dice.to_string().fg(Theme::SECONDARY).bold(),
+                        ]),
+                    });
+                } else if let Some(category) = hint.category {
+                    lines.push(Line::from(vec![
+                        "→ ".fg(Theme::TEXT),
+                        short_label(category, Ruleset::Standard).fg(Theme::SECONDARY).bold(),
+                    ]));
+                } else {
+                    lines.push(Line::from("Roll".fg(Theme::SECONDARY).bold()));
+                }
+                lines.push(Line::from(format!("EV {:.1}", hint.expected).fg(Theme::TEXT)));
+                if let Some(loss) = hint.loss {
+                    let color = if loss < 0.05 { Theme::TEXT_DIM } else { Theme::ACCENT };
+                    lines.push(Line::from(format!("Loss {loss:.1}").fg(color)));
+                } else {
+                    lines.push(Line::from(""));
+                }
+            }
+        }
+        lines.resize(4, Line::from(""));
+        lines.push(Line::from(vec![
+            "SCORE: ".fg(Theme::TEXT),
+            format!("{}", self.score).fg(Theme::PRIMARY).bold(),
+        ]));
+
+        Paragraph::new(lines).right_aligned().render(area, buf);
+    }
+}
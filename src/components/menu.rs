@@ -0,0 +1,39 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    text::Line,
+    widgets::{Paragraph, Widget},
+};
+
+use crate::theme::Theme;
+
+pub struct Menu<'a> {
+    pub items: &'a [&'a str],
+    pub selected: usize,
+}
+
+impl<'a> Menu<'a> {
+    pub fn new(items: &'a [&'a str], selected: usize) -> Menu<'a> {
+        Menu { items, selected }
+    }
+}
+
+impl Widget for Menu<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let lines: Vec<Line> = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                if i == self.selected {
+                    Line::from(format!("▸ {item} ◂")).fg(Theme::ACCENT).bold()
+                } else {
+                    Line::from(item.to_string()).fg(Theme::TEXT)
+                }
+            })
+            .collect();
+
+        Paragraph::new(lines).centered().render(area, buf);
+    }
+}
@@ -8,30 +8,40 @@ use ratatui::{
 };
 
 use crate::{
-    app::DieFace,
-    model::roll::{Roll, RollType},
-    score_util::{calc_score, is_yahtzee},
+    model::{die::DieFace, roll::{AllRolls, Roll, RollType}, ruleset::Ruleset},
+    score_util::{calc_score, can_score, is_yahtzee},
     theme::Theme,
 };
 
 pub struct RollSlot<'a> {
     pub roll: Roll,
-    pub faces: &'a Vec<DieFace>,
+    pub rolls: &'a AllRolls,
+    pub faces: &'a [DieFace],
     pub roll_count: u8,
+    /// Ordered games place a Joker by the order rather than the Joker rules.
+    pub ordered: bool,
 }
 
-impl RollSlot<'_> {
-    pub fn new(roll: Roll, faces: &Vec<DieFace>, roll_count: u8) -> RollSlot<'_> {
+impl<'a> RollSlot<'a> {
+    pub fn new(roll: Roll, rolls: &'a AllRolls, faces: &'a [DieFace], roll_count: u8) -> RollSlot<'a> {
         RollSlot {
             roll,
+            rolls,
             faces,
             roll_count,
+            ordered: false,
         }
     }
+
+    pub fn ordered(mut self, ordered: bool) -> RollSlot<'a> {
+        self.ordered = ordered;
+        self
+    }
 }
 
 impl Widget for RollSlot<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
+        let yacht = self.rolls.ruleset == Ruleset::Yacht;
         let label = match self.roll.roll_type {
             RollType::Ones => num_cat_block(String::from("1")),
             RollType::Twos => num_cat_block(String::from("2")),
@@ -39,17 +49,34 @@ impl Widget for RollSlot<'_> {
             RollType::Fours => num_cat_block(String::from("4")),
             RollType::Fives => num_cat_block(String::from("5")),
             RollType::Sixes => num_cat_block(String::from("6")),
+            RollType::OnePair => cat_block(String::from("One"), String::from("Pair")),
+            RollType::TwoPairs => cat_block(String::from("Two"), String::from("Pairs")),
+            RollType::ThreePairs => cat_block(String::from("Three"), String::from("Pairs")),
             RollType::ThreeOfAKind => cat_block(String::from("3/"), String::from("Kind")),
             RollType::FourOfAKind => cat_block(String::from("4/"), String::from("Kind")),
             RollType::FullHouse => cat_block(String::from("Full"), String::from("House")),
+            RollType::Castle => cat_block(String::from(""), String::from("Castle")),
+            RollType::Tower => cat_block(String::from(""), String::from("Tower")),
+            RollType::SmallStraight if yacht => {
+                cat_block(String::from("Little"), String::from("Str8"))
+            }
             RollType::SmallStraight => {
                 cat_block(String::from("Small"), String::from("Str8"))
             }
+            RollType::LargeStraight if yacht => {
+                cat_block(String::from("Big"), String::from("Str8"))
+            }
             RollType::LargeStraight => {
                 cat_block(String::from("Large"), String::from("Str8"))
             }
+            RollType::FullStraight => cat_block(String::from("Full"), String::from("Str8")),
+            RollType::Chance if yacht => cat_block(String::from(""), String::from("Choice")),
             RollType::Chance => cat_block(String::from(""), String::from("Chance")),
-            RollType::Yahtzee => yahtzee_block(self.roll),
+            RollType::Yahtzee if yacht => cat_block(String::from(""), String::from("Yacht")),
+            RollType::Yahtzee if self.rolls.ruleset == Ruleset::Maxi => {
+                cat_block(String::from("Maxi"), String::from("Yahtzee"))
+            }
+            RollType::Yahtzee => yahtzee_block(self.rolls.yahtzee_bonus_count()),
         };
 
         let label_area = Layout::default()
@@ -57,8 +84,12 @@ impl Widget for RollSlot<'_> {
             .constraints([Constraint::Length(3), Constraint::Min(1)])
             .split(area);
 
-        if self.roll.selected {
-            let possible_score = calc_score(self.roll, self.faces);
+        if self.roll.selected && !self.ordered && !can_score(self.roll.roll_type, self.rolls, self.faces) {
+            // joker rules force the bonus yahtzee into another slot
+            label.fg(Theme::ACCENT).render(label_area[0], buf);
+            Line::from("✗").centered().fg(Theme::TEXT_DIM).render(label_area[1], buf);
+        } else if self.roll.selected {
+            let possible_score = calc_score(self.roll, self.rolls, self.faces);
 
             label.fg(Theme::ACCENT).render(label_area[0], buf);
             Line::from(possible_score.to_string())
@@ -66,12 +97,13 @@ impl Widget for RollSlot<'_> {
                 .fg(Theme::ACCENT)
                 .render(label_area[1], buf);
         } else if self.roll.roll_type == RollType::Yahtzee
+            && self.rolls.yahtzee_bonus().is_some()
             && self.roll.score.unwrap_or(0) >= 50
             && self.roll_count > 0
             && !self.faces.iter().any(|face| face.is_rolling())
             && is_yahtzee(self.faces)
         {
-            let possible_score = calc_score(self.roll, self.faces);
+            let possible_score = calc_score(self.roll, self.rolls, self.faces);
 
             label.fg(Theme::SECONDARY).render(label_area[0], buf);
             Line::from(possible_score.to_string())
@@ -86,13 +118,15 @@ impl Widget for RollSlot<'_> {
 }
 
 pub struct BonusSlot {
+    pub threshold: u32,
     pub progress: u32,
     pub score: u32,
 }
 
 impl BonusSlot {
-    pub fn new(status: (u32, u32)) -> BonusSlot {
+    pub fn new(threshold: u32, status: (u32, u32)) -> BonusSlot {
         BonusSlot {
+            threshold,
             progress: status.0,
             score: status.1,
         }
@@ -103,7 +137,7 @@ impl Widget for BonusSlot {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let label = cat_block(
             String::from("Bonus"),
-            format!("{}/63", self.progress),
+            format!("{}/{}", self.progress, self.threshold),
         );
         let label_area = Layout::default()
             .direction(Direction::Vertical)
@@ -119,7 +153,7 @@ fn num_cat_block(n: String) -> Paragraph<'static> {
     let block = Block::bordered()
         .padding(Padding::horizontal(2))
         .border_set(border::ROUNDED);
-    return Paragraph::new(n).block(block);
+    Paragraph::new(n).block(block)
 }
 
 fn cat_block(line_1: String, line_2: String) -> Paragraph<'static> {
@@ -131,12 +165,12 @@ fn cat_block(line_1: String, line_2: String) -> Paragraph<'static> {
     .centered()
 }
 
-fn yahtzee_block(roll: Roll) -> Paragraph<'static> {
-    match yahtzee_bonus_count_from_score(roll.score) {
+fn yahtzee_block(bonus_count: u32) -> Paragraph<'static> {
+    match bonus_count {
         0 => cat_block(String::from(""), String::from("Yahtzee")),
         c if c <= 6 => cat_block(
             String::from("Yahtzee"),
-            '★'.to_string().repeat(c.into()),
+            '★'.to_string().repeat(c as usize),
         ),
         _ => cat_block(
             String::from("Yahtzee"),
@@ -146,17 +180,10 @@ fn yahtzee_block(roll: Roll) -> Paragraph<'static> {
 }
 
 fn score(score: Option<u32>) -> Line<'static> {
-    return match score {
+    match score {
         Some(s) => Line::from(s.to_string()).centered().fg(Theme::PRIMARY),
         None => Line::from(String::from("---"))
             .centered()
             .fg(Theme::TEXT_DIM),
-    };
-}
-
-fn yahtzee_bonus_count_from_score(score: Option<u32>) -> u8 {
-    match score {
-        Some(s) if s >= 50 => ((s - 50) / 100) as u8,
-        _ => 0,
     }
 }
@@ -1,81 +1,113 @@
 use ratatui::{
-    buffer::Buffer, layout::{Constraint, Direction, Layout, Rect}, widgets::Widget
+    buffer::Buffer, layout::{Constraint, Direction, Layout, Rect}, style::Stylize, text::Line, widgets::Widget
 };
 
-use crate::{app::DieFace, components::roll_slot::{BonusSlot, RollSlot}, model::roll::AllRolls};
+use crate::{components::roll_slot::{BonusSlot, RollSlot}, model::{die::DieFace, player::Player, roll::{AllRolls, Roll}, ruleset::Ruleset}, theme::Theme};
+
+const SLOTS_PER_ROW: usize = 7;
+const SLOT_HEIGHT: u16 = 4;
 
 pub struct RollSlots<'a> {
-    pub rolls: AllRolls,
-    pub faces: &'a Vec<DieFace>,
+    /// Every column of the scorecard; only the one being worked on is shown
+    /// in full, the others as totals above it.
+    pub columns: &'a [AllRolls],
+    pub column: usize,
+    pub faces: &'a [DieFace],
     pub roll_count: u8,
+    /// Whether the categories are filled in order.
+    pub ordered: bool,
+    /// Whose scorecard this is, shown above the slots in multiplayer games.
+    pub player_name: Option<&'a str>,
+    /// The latest move, shown next to the player's name.
+    pub last_action: Option<&'a str>,
+}
+
+impl RollSlots<'_> {
+    /// Height of the slots of a `ruleset` scorecard, without the lines above them.
+    pub fn grid_height(ruleset: Ruleset) -> u16 {
+        let lower = ruleset.categories().iter().filter(|r| !r.is_upper()).count();
+        let rows = 1 + lower.div_ceil(SLOTS_PER_ROW) as u16;
+        rows * SLOT_HEIGHT + rows - 1
+    }
 }
 
 impl Widget for RollSlots<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = match self.player_name {
+            Some(name) => {
+                let [header, rest] = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(area);
+                let mut line = Line::from(vec![
+                    "▸ ".fg(Theme::ACCENT),
+                    name.to_string().fg(Theme::ACCENT).bold(),
+                    "'s turn".fg(Theme::TEXT),
+                ]);
+                if let Some(action) = self.last_action {
+                    line.push_span(format!(" · {action}").fg(Theme::TEXT_DIM));
+                }
+                line.centered().render(header, buf);
+                rest
+            }
+            None => area,
+        };
+        let area = if self.columns.len() > 1 {
+            let [header, rest] = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(area);
+            let mut line = Line::default();
+            for (i, rolls) in self.columns.iter().enumerate() {
+                if i > 0 {
+                    line.push_span("   ".fg(Theme::TEXT_DIM));
+                }
+                let tab = format!("x{} {}", Player::weight(i), rolls.total_score());
+                if i == self.column {
+                    line.push_span("▸".fg(Theme::ACCENT));
+                    line.push_span(tab.fg(Theme::ACCENT).bold());
+                } else {
+                    line.push_span(format!(" {tab}").fg(Theme::TEXT_DIM));
+                }
+            }
+            line.centered().render(header, buf);
+            rest
+        } else {
+            area
+        };
+        let rolls = &self.columns[self.column];
+
+        // the upper section and its bonus take the first row, the lower
+        // section as many rows as it needs
+        let (upper, lower): (Vec<&Roll>, Vec<&Roll>) = rolls.iter().partition(|roll| roll.roll_type.is_upper());
+        let lower_rows = lower.len().div_ceil(SLOTS_PER_ROW);
         let rows = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(4), // First row - 5 chars tall
-                Constraint::Length(4), // Second row - 5 chars tall
-            ])
+            .constraints((0..=lower_rows).map(|_| Constraint::Length(SLOT_HEIGHT)))
             .spacing(1)
             .split(area);
 
-        // Create 7 columns for each row (7 chars wide each)
-        let constraints: Vec<Constraint> = (0..7).map(|_| Constraint::Length(7)).collect();
-
-        let top_row_slots = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(constraints.clone())
-            .spacing(1)
-            .split(rows[0]);
-
-        let bottom_row_slots = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(constraints)
-            .spacing(1) 
-            .split(rows[1]);
-
-        let one_slot = RollSlot::new(self.rolls.ones_roll, self.faces, self.roll_count);
-        one_slot.render(top_row_slots[0], buf);
-
-        let two_slot = RollSlot::new(self.rolls.twos_roll, self.faces, self.roll_count);
-        two_slot.render(top_row_slots[1], buf);
-
-        let three_slot = RollSlot::new(self.rolls.threes_roll, self.faces, self.roll_count);
-        three_slot.render(top_row_slots[2], buf);
-
-        let four_slot = RollSlot::new(self.rolls.fours_roll, self.faces, self.roll_count);
-        four_slot.render(top_row_slots[3], buf);
-
-        let five_slot = RollSlot::new(self.rolls.fives_roll, self.faces, self.roll_count);
-        five_slot.render(top_row_slots[4], buf);
-
-        let six_slot = RollSlot::new(self.rolls.sixes_roll, self.faces, self.roll_count);
-        six_slot.render(top_row_slots[5], buf);
-
-        let bonus_slot = BonusSlot::new(self.rolls.bonus_status());
-        bonus_slot.render(top_row_slots[6], buf);
-
-        let three_of_a_kind_slot = RollSlot::new(self.rolls.three_of_a_kind_roll, self.faces, self.roll_count);
-        three_of_a_kind_slot.render(bottom_row_slots[0], buf);
-
-        let four_of_a_kind_slot = RollSlot::new(self.rolls.four_of_a_kind_roll, self.faces, self.roll_count);
-        four_of_a_kind_slot.render(bottom_row_slots[1], buf);
-
-        let full_house_slot = RollSlot::new(self.rolls.full_house_roll, self.faces, self.roll_count);
-        full_house_slot.render(bottom_row_slots[2], buf);
-
-        let small_straight_slot = RollSlot::new(self.rolls.small_straight_roll, self.faces, self.roll_count);
-        small_straight_slot.render(bottom_row_slots[3], buf);
-
-        let large_straight_slot = RollSlot::new(self.rolls.large_straight_roll, self.faces, self.roll_count);
-        large_straight_slot.render(bottom_row_slots[4], buf);
-
-        let chance_slot = RollSlot::new(self.rolls.chance_roll, self.faces, self.roll_count);
-        chance_slot.render(bottom_row_slots[5], buf);
-
-        let yahtzee_slot = RollSlot::new(self.rolls.yahtzee_roll, self.faces, self.roll_count);
-        yahtzee_slot.render(bottom_row_slots[6], buf);
+        // 7 chars wide each
+        let constraints: Vec<Constraint> = (0..SLOTS_PER_ROW).map(|_| Constraint::Length(7)).collect();
+        let row_slots = |row: Rect| {
+            Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(constraints.clone())
+                .spacing(1)
+                .split(row)
+        };
+
+        let top_row_slots = row_slots(rows[0]);
+        for (roll, slot) in upper.into_iter().zip(top_row_slots.iter()) {
+            RollSlot::new(*roll, rolls, self.faces, self.roll_count)
+                .ordered(self.ordered)
+                .render(*slot, buf);
+        }
+        if let Some(bonus) = rolls.upper_bonus() {
+            let bonus_slot = BonusSlot::new(bonus.threshold, rolls.bonus_status());
+            bonus_slot.render(top_row_slots[SLOTS_PER_ROW - 1], buf);
+        }
+
+        for (chunk, row) in lower.chunks(SLOTS_PER_ROW).zip(rows.iter().skip(1)) {
+            for (roll, slot) in chunk.iter().zip(row_slots(*row).iter()) {
+                RollSlot::new(**roll, rolls, self.faces, self.roll_count)
+                    .ordered(self.ordered)
+                    .render(*slot, buf);
+            }
+        }
     }
 }
@@ -0,0 +1,127 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{Style, Stylize},
+    widgets::{Cell, Row, Table, Widget},
+};
+
+use crate::{
+    model::{player::Player, roll::RollType, ruleset::Ruleset},
+    theme::Theme,
+};
+
+const NAME_WIDTH: usize = 6;
+
+/// Every player's scorecard side by side.
+pub struct Standings<'a> {
+    pub players: &'a [Player],
+    /// Column to highlight, such as the player whose turn it is.
+    pub current: Option<usize>,
+}
+
+impl<'a> Standings<'a> {
+    pub fn new(players: &'a [Player], current: Option<usize>) -> Standings<'a> {
+        Standings { players, current }
+    }
+}
+
+impl Widget for Standings<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let names = self.players.iter().enumerate().map(|(i, p)| {
+            let name: String = p.name.chars().take(NAME_WIDTH).collect();
+            let color = if self.current == Some(i) {
+                Theme::ACCENT
+            } else {
+                Theme::SECONDARY
+            };
+            Cell::from(name).fg(color)
+        });
+        let header = Row::new(std::iter::once(Cell::from("")).chain(names))
+            .style(Style::new().bold());
+
+        let mut rows: Vec<Row> = if self.players[0].columns.len() > 1 {
+            // a row per category would not fit every column, so only their totals are shown
+            (0..self.players[0].columns.len())
+                .map(|column| {
+                    Row::new(std::iter::once(format!("x{}", Player::weight(column))).chain(
+                        self.players
+                            .iter()
+                            .map(|p| p.columns[column].total_score().to_string()),
+                    ))
+                    .style(Style::new().fg(Theme::TEXT))
+                })
+                .collect()
+        } else {
+            let ruleset = self.players[0].rolls().ruleset;
+            let roll_types: Vec<RollType> = self.players[0].rolls().iter().map(|r| r.roll_type).collect();
+            roll_types
+                .iter()
+                .map(|roll_type| {
+                    Row::new(std::iter::once(short_label(*roll_type, ruleset).to_string()).chain(
+                        self.players.iter().map(|p| match p.rolls().roll(*roll_type).score {
+                            Some(score) => score.to_string(),
+                            None => String::from("-"),
+                        }),
+                    ))
+                    .style(Style::new().fg(Theme::TEXT))
+                })
+                .collect()
+        };
+        if self.players[0].rolls().upper_bonus().is_some() {
+            rows.push(
+                Row::new(std::iter::once(String::from("Bonus")).chain(
+                    self.players.iter().map(|p| p.upper_bonus().to_string()),
+                ))
+                .style(Style::new().fg(Theme::TEXT_DIM)),
+            );
+        }
+        rows.push(
+            Row::new(std::iter::once(String::from("Total")).chain(
+                self.players.iter().map(|p| p.total_score().to_string()),
+            ))
+            .style(Style::new().fg(Theme::PRIMARY).bold()),
+        );
+
+        let widths = std::iter::once(Constraint::Length(7))
+            .chain(self.players.iter().map(|_| Constraint::Length(NAME_WIDTH as u16)));
+
+        Table::new(rows, widths)
+            .header(header)
+            .column_spacing(1)
+            .render(area, buf);
+    }
+}
+
+pub fn short_label(roll_type: RollType, ruleset: Ruleset) -> &'static str {
+    match (roll_type, ruleset) {
+        (RollType::SmallStraight, Ruleset::Yacht) => "Little",
+        (RollType::LargeStraight, Ruleset::Yacht) => "Big",
+        (RollType::Chance, Ruleset::Yacht) => "Choice",
+        (RollType::Yahtzee, Ruleset::Yacht) => "Yacht",
+        _ => label(roll_type),
+    }
+}
+
+fn label(roll_type: RollType) -> &'static str {
+    match roll_type {
+        RollType::Ones => "Ones",
+        RollType::Twos => "Twos",
+        RollType::Threes => "Threes",
+        RollType::Fours => "Fours",
+        RollType::Fives => "Fives",
+        RollType::Sixes => "Sixes",
+        RollType::OnePair => "1 Pair",
+        RollType::TwoPairs => "2 Pairs",
+        RollType::ThreePairs => "3 Pairs",
+        RollType::ThreeOfAKind => "3/Kind",
+        RollType::FourOfAKind => "4/Kind",
+        RollType::FullHouse => "F House",
+        RollType::Castle => "Castle",
+        RollType::Tower => "Tower",
+        RollType::SmallStraight => "Sm Str8",
+        RollType::LargeStraight => "Lg Str8",
+        RollType::FullStraight => "Fl Str8",
+        RollType::Chance => "Chance",
+        RollType::Yahtzee => "Yahtzee",
+    }
+}
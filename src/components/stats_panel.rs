@@ -0,0 +1,117 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Flex, Layout, Margin, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Row, Sparkline, Table, Widget},
+};
+
+use crate::{
+    components::standings::short_label,
+    high_scores::Variant,
+    model::roll::{AllRolls, RollType},
+    stats::{CategoryStats, Summary},
+    theme::Theme,
+};
+
+/// Lifetime statistics: the totals, a sparkline of recent scores and how
+/// each category has gone.
+pub struct StatsPanel<'a> {
+    pub summary: Option<&'a Summary>,
+    pub variant: Variant,
+}
+
+impl<'a> StatsPanel<'a> {
+    pub fn new(summary: Option<&'a Summary>, variant: Variant) -> StatsPanel<'a> {
+        StatsPanel { summary, variant }
+    }
+
+    fn table(&self, categories: Vec<&CategoryStats>) -> Table<'_> {
+        let header = Row::new(["", "Avg", "0s"]).style(Style::new().fg(Theme::SECONDARY).bold());
+        let rows = categories.into_iter().map(|c| {
+            Row::new([
+                short_label(c.category, self.variant.ruleset).to_string(),
+                format!("{:.1}", c.average),
+                percent(c.scratch_rate),
+            ])
+            .style(Style::new().fg(Theme::TEXT))
+        });
+        Table::new(rows, [Constraint::Length(7), Constraint::Length(5), Constraint::Length(4)])
+            .header(header)
+            .column_spacing(1)
+    }
+}
+
+impl Widget for StatsPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let Some(summary) = self.summary else {
+            Line::from("No games played yet".fg(Theme::TEXT_DIM))
+                .centered()
+                .render(area, buf);
+            return;
+        };
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Length(2),
+                Constraint::Length(1),
+                Constraint::Min(1),
+            ])
+            .split(area);
+
+        let label = |text: &str| format!("{text} ").fg(Theme::TEXT);
+        let value = |text: String| format!("{text}  ").fg(Theme::PRIMARY).bold();
+        Line::from(vec![
+            label("Games"),
+            value(summary.games.to_string()),
+            label("Mean"),
+            value(format!("{:.1}", summary.mean)),
+            label("Median"),
+            value(format!("{}", summary.median)),
+            label("Best"),
+            value(summary.best.to_string()),
+        ])
+        .centered()
+        .render(layout[0], buf);
+        let rules = AllRolls::with_rules(self.variant.ruleset, self.variant.house_rules);
+        let mut rates = Vec::new();
+        if rules.upper_bonus().is_some() {
+            rates.extend([label("Bonus"), value(percent(summary.upper_bonus_rate))]);
+        }
+        let yahtzee = short_label(RollType::Yahtzee, self.variant.ruleset);
+        rates.extend([label(yahtzee), value(percent(summary.yahtzee_rate))]);
+        if rules.yahtzee_bonus().is_some() {
+            rates.extend([label(&format!("Bonus {yahtzee}")), value(percent(summary.bonus_yahtzee_rate))]);
+        }
+        Line::from(rates)
+            .centered()
+            .render(Rect { y: layout[0].y + 1, height: 1, ..layout[0] }, buf);
+
+        // the most recent scores that fit, one bar each
+        let sparkline_area = layout[1].inner(Margin::new(2, 0));
+        let recent = &summary.totals[summary.totals.len().saturating_sub(sparkline_area.width as usize)..];
+        let data: Vec<u64> = recent.iter().map(|t| u64::from(*t)).collect();
+        Sparkline::default()
+            .data(&data)
+            .max(u64::from(summary.best))
+            .style(Style::new().fg(Theme::ACCENT))
+            .render(sparkline_area, buf);
+
+        let (upper, lower): (Vec<&CategoryStats>, Vec<&CategoryStats>) =
+            summary.categories.iter().partition(|c| c.category.is_upper());
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(20), Constraint::Length(20)])
+            .flex(Flex::Center)
+            .spacing(4)
+            .split(layout[3]);
+        Widget::render(self.table(upper), columns[0], buf);
+        Widget::render(self.table(lower), columns[1], buf);
+    }
+}
+
+fn percent(rate: f64) -> String {
+    format!("{:.0}%", rate * 100.0)
+}
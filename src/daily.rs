@@ -0,0 +1,185 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, btree_map::Entry},
+    fs, io,
+    path::PathBuf,
+};
+
+use chrono::{Datelike, Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::{bot, model::{player::Player, roll::AllRolls}, storage};
+
+const DAILY_VERSION: u32 = 1;
+
+/// The seed everyone's challenge on `date` is rolled with, such as
+/// 20250131 for the last day of January 2025.
+pub fn seed_for(date: NaiveDate) -> u64 {
+    date.year() as u64 * 10_000 + u64::from(date.month()) * 100 + u64::from(date.day())
+}
+
+/// The score of each day's challenge. Only the first try counts, so
+/// playing the same dice again cannot improve it, and a first try given up
+/// scores nothing.
+#[derive(Serialize, Deserialize)]
+pub struct DailyResults {
+    version: u32,
+    /// The score of the first try of each day, for days it was finished.
+    #[serde(alias = "best")]
+    pub scores: BTreeMap<NaiveDate, u32>,
+    /// Days whose challenge has been rolled, finished or not.
+    #[serde(default)]
+    tried: BTreeSet<NaiveDate>,
+}
+
+impl Default for DailyResults {
+    fn default() -> Self {
+        DailyResults::new()
+    }
+}
+
+impl DailyResults {
+    pub fn new() -> DailyResults {
+        DailyResults {
+            version: DAILY_VERSION,
+            scores: BTreeMap::new(),
+            tried: BTreeSet::new(),
+        }
+    }
+
+    /// Loads the results from disk, starting afresh if there are none.
+    pub fn load() -> DailyResults {
+        daily_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str::<DailyResults>(&contents).ok())
+            .filter(|results| results.version == DAILY_VERSION)
+            .unwrap_or_default()
+    }
+
+    pub fn store(&self) -> io::Result<()> {
+        let Some(path) = daily_path() else {
+            return Ok(());
+        };
+        let json = serde_json::to_vec_pretty(self).map_err(io::Error::other)?;
+        storage::write_atomic(&path, &json)
+    }
+
+    /// Whether the challenge on `date` has already had its try.
+    pub fn is_tried(&self, date: NaiveDate) -> bool {
+        self.tried.contains(&date) || self.scores.contains_key(&date)
+    }
+
+    /// Uses up the try on `date`, once its first roll is made.
+    pub fn start(&mut self, date: NaiveDate) {
+        self.tried.insert(date);
+    }
+
+    /// Counts the finished first try on `date`, returning false if the day
+    /// already has a score.
+    pub fn record(&mut self, date: NaiveDate, total: u32) -> bool {
+        match self.scores.entry(date) {
+            Entry::Vacant(entry) => {
+                entry.insert(total);
+                true
+            }
+            Entry::Occupied(_) => false,
+        }
+    }
+
+    /// Days in a row, up to and including `date`, with a finished challenge.
+    pub fn streak(&self, date: NaiveDate) -> u32 {
+        let mut streak = 0;
+        let mut day = Some(date);
+        while let Some(d) = day.filter(|d| self.scores.contains_key(d)) {
+            streak += 1;
+            day = d.checked_sub_days(Days::new(1));
+        }
+        streak
+    }
+}
+
+/// A line of squares for `rolls`, one per category: green at or above
+/// par, yellow below it and black when scratched.
+pub fn grid(rolls: &AllRolls, upper: bool) -> String {
+    rolls
+        .iter()
+        .filter(|r| r.roll_type.is_upper() == upper)
+        .map(|r| match r.score.unwrap_or(0) {
+            0 => '⬛',
+            s if s as f32 >= bot::par(r.roll_type) => '🟩',
+            _ => '🟨',
+        })
+        .collect()
+}
+
+/// The result of `player`'s challenge on `date`, to paste into a chat.
+pub fn share_text(date: NaiveDate, player: &Player, streak: u32) -> String {
+    let rolls = player.rolls();
+    let mut text = format!("Rahtzee Daily {date}: {}", player.total_score());
+    if streak > 1 {
+        text.push_str(&format!(" 🔥{streak}"));
+    }
+    text.push('\n');
+    text.push_str(&grid(rolls, true));
+    if player.upper_bonus() > 0 {
+        text.push_str(&format!(" +{}", player.upper_bonus()));
+    }
+    text.push('\n');
+    text.push_str(&grid(rolls, false));
+    text.push_str(&"⭐".repeat(player.yahtzee_bonus_count() as usize));
+    text
+}
+
+fn daily_path() -> Option<PathBuf> {
+    storage::data_dir().map(|dir| dir.join("daily.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::roll::RollType;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+    }
+
+    #[test]
+    fn test_seed_follows_the_date() {
+        assert_eq!(seed_for(date(7)), 20250307);
+        assert_ne!(seed_for(date(7)), seed_for(date(8)));
+    }
+
+    #[test]
+    fn test_first_try_and_streak() {
+        let mut results = DailyResults::new();
+        assert!(!results.is_tried(date(1)));
+        results.start(date(1));
+        assert!(results.is_tried(date(1)));
+        assert!(results.record(date(1), 180));
+        assert!(!results.record(date(1), 210));
+        assert_eq!(results.scores[&date(1)], 180);
+
+        // results kept before tries were tracked still count as tried
+        let old: DailyResults = serde_json::from_str(r#"{"version":1,"best":{"2025-03-02":150}}"#).unwrap();
+        assert!(old.is_tried(date(2)));
+
+        results.record(date(3), 200);
+        results.record(date(4), 190);
+        assert_eq!(results.streak(date(4)), 2);
+        assert_eq!(results.streak(date(5)), 0);
+        assert_eq!(results.streak(date(1)), 1);
+    }
+
+    #[test]
+    fn test_share_text() {
+        let mut player = Player::new("a");
+        let scores = [3, 6, 0, 16, 20, 24, 25, 0, 25, 30, 40, 22, 150];
+        for (roll_type, score) in RollType::STANDARD.into_iter().zip(scores) {
+            player.columns[0].roll_mut(roll_type).score = Some(score);
+        }
+        let text = share_text(date(7), &player, 3);
+        assert_eq!(
+            text,
+            "Rahtzee Daily 2025-03-07: 396 🔥3\n🟩🟩⬛🟩🟩🟩 +35\n🟩⬛🟩🟩🟩🟩🟩⭐"
+        );
+    }
+}
@@ -3,13 +3,24 @@ use std::time::Duration;
 
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
 
-use crate::app::App;
+use crate::app::{App, Screen};
+use crate::keymap::Action;
 
 pub fn handle_events(app: &mut App) -> io::Result<()> {
     // Only use short timeout when rolling, otherwise block waiting for events
+    app.poll_solver();
+    app.poll_network()?;
+    if app.is_bot_turn() {
+        app.step_bot()?;
+    }
+    app.tick_replay();
     let timeout = if app.is_rolling() {
         app.update_dice_animation();
         Duration::from_millis(100)
+    } else if app.is_replay_running() || app.is_network_game() {
+        Duration::from_millis(50)
+    } else if app.is_bot_turn() || app.is_loading_solver() {
+        Duration::from_millis(250)
     } else {
         Duration::from_secs(30) // Long timeout when not animating
     };
@@ -17,7 +28,7 @@ pub fn handle_events(app: &mut App) -> io::Result<()> {
     if event::poll(timeout)? {
         match event::read()? {
             Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
-                handle_key_event(app, key_event)
+                handle_key_event(app, key_event)?
             }
             _ => {}
         }
@@ -25,40 +36,192 @@ pub fn handle_events(app: &mut App) -> io::Result<()> {
     Ok(())
 }
 
-fn handle_key_event(app: &mut App, key_event: KeyEvent) {
-    match key_event.code {
-        KeyCode::Char('q') => app.exit(),
-        KeyCode::Char('r') => {
-            if !app.is_rolling() && !app.is_game_over() {
-                app.start_roll();
-            }
-        }
-        KeyCode::Char('1'..='5') => {
-            if !app.is_rolling() && app.roll_count != 0 && !app.is_game_over() {
-                if let KeyCode::Char(c) = key_event.code {
-                    if let Some(digit) = c.to_digit(10) {
-                        app.toggle_hold((digit - 1) as usize);
-                    }
-                }
-            }
-        }
-        KeyCode::Char('l') | KeyCode::Right => {
-            if app.roll_count > 0 {
-                app.rolls.select_next()
-            }
-        }
-        KeyCode::Char('h') | KeyCode::Left => {
-            if app.roll_count > 0 {
-                app.rolls.select_prev()
-            }
+fn handle_key_event(app: &mut App, key_event: KeyEvent) -> io::Result<()> {
+    match app.screen {
+        Screen::Title => handle_title_key(app, key_event),
+        Screen::Setup => handle_setup_key(app, key_event),
+        Screen::Settings => handle_settings_key(app, key_event)?,
+        Screen::HighScores | Screen::Statistics | Screen::Help => handle_high_scores_key(app, key_event),
+        Screen::Standings => handle_standings_key(app, key_event),
+        Screen::Log => handle_log_key(app, key_event),
+        Screen::NameEntry => handle_name_entry_key(app, key_event)?,
+        Screen::Playing => handle_playing_key(app, key_event)?,
+        Screen::Replay => handle_replay_key(app, key_event),
+    }
+    Ok(())
+}
+
+// Menus and the other screens choose with the score key and go back with
+// the menu key. Where names are typed, characters and Backspace edit the
+// name, unless they are bound to finishing or leaving it.
+
+fn handle_title_key(app: &mut App, key_event: KeyEvent) {
+    match app.keymap.action(key_event.code) {
+        Some(Action::Quit) => app.exit(),
+        Some(Action::Down) => app.select_next_menu_item(),
+        Some(Action::Up) => app.select_prev_menu_item(),
+        Some(Action::Score) => app.choose_menu_item(),
+        _ => {}
+    }
+}
+
+fn handle_setup_key(app: &mut App, key_event: KeyEvent) {
+    let action = app.keymap.action(key_event.code);
+    let finishes = matches!(
+        action,
+        Some(Action::Score | Action::Menu | Action::AddPlayer | Action::RemovePlayer)
+    );
+    if app.is_naming_player() && !finishes {
+        match key_event.code {
+            KeyCode::Char(c) => return app.push_player_name_char(c),
+            KeyCode::Backspace => return app.pop_player_name_char(),
+            _ => {}
         }
-        KeyCode::Enter => {
+    }
+    match action {
+        Some(Action::Down) => app.select_next_setup_row(),
+        Some(Action::Up) => app.select_prev_setup_row(),
+        Some(Action::Next) => app.cycle_setup_option(true),
+        Some(Action::Prev) => app.cycle_setup_option(false),
+        Some(Action::AddPlayer) => app.add_player(),
+        Some(Action::RemovePlayer) => app.remove_player(),
+        Some(Action::Score) => app.start_new_game(),
+        Some(Action::Menu) => app.show_title(),
+        _ => {}
+    }
+}
+
+fn handle_settings_key(app: &mut App, key_event: KeyEvent) -> io::Result<()> {
+    match app.keymap.action(key_event.code) {
+        Some(Action::Quit) => app.exit(),
+        Some(Action::Down) => app.select_next_setting(),
+        Some(Action::Up) => app.select_prev_setting(),
+        Some(Action::Next) => app.change_setting(true)?,
+        Some(Action::Prev) => app.change_setting(false)?,
+        Some(Action::Score) => app.change_setting(true)?,
+        Some(Action::Menu) => app.show_title(),
+        _ => {}
+    }
+    Ok(())
+}
+
+fn handle_standings_key(app: &mut App, key_event: KeyEvent) {
+    match app.keymap.action(key_event.code) {
+        Some(Action::Quit) => app.exit(),
+        Some(Action::Table) => app.toggle_standings(),
+        Some(Action::Log) => app.toggle_log(),
+        Some(Action::Score | Action::Menu) => app.toggle_standings(),
+        _ => {}
+    }
+}
+
+fn handle_log_key(app: &mut App, key_event: KeyEvent) {
+    match app.keymap.action(key_event.code) {
+        Some(Action::Quit) => app.exit(),
+        Some(Action::Log | Action::Score | Action::Menu) => app.toggle_log(),
+        _ => {}
+    }
+}
+
+fn handle_high_scores_key(app: &mut App, key_event: KeyEvent) {
+    match app.keymap.action(key_event.code) {
+        Some(Action::Quit) => app.exit(),
+        Some(Action::Score | Action::Menu) => app.show_title(),
+        _ => {}
+    }
+}
+
+fn handle_name_entry_key(app: &mut App, key_event: KeyEvent) -> io::Result<()> {
+    match app.keymap.action(key_event.code) {
+        Some(Action::Score) => app.submit_name()?,
+        Some(Action::Menu) => app.skip_name_entry(),
+        _ => match key_event.code {
+            KeyCode::Char(c) => app.push_name_char(c),
+            KeyCode::Backspace => app.pop_name_char(),
+            _ => {}
+        },
+    }
+    Ok(())
+}
+
+fn handle_replay_key(app: &mut App, key_event: KeyEvent) {
+    match app.keymap.action(key_event.code) {
+        Some(Action::Quit | Action::Menu) => app.exit(),
+        Some(Action::Next) => app.step_replay(true),
+        Some(Action::Prev) => app.step_replay(false),
+        Some(Action::Table) => app.toggle_standings(),
+        Some(Action::Log) => app.toggle_log(),
+        Some(Action::Hint) => app.toggle_hint(),
+        Some(Action::Pause) => app.toggle_replay_pause(),
+        Some(Action::Faster) => app.change_replay_speed(true),
+        Some(Action::Slower) => app.change_replay_speed(false),
+        _ => {}
+    }
+}
+
+fn handle_playing_key(app: &mut App, key_event: KeyEvent) -> io::Result<()> {
+    let Some(action) = app.keymap.action(key_event.code) else {
+        return Ok(());
+    };
+    match action {
+        Action::Quit => app.exit(),
+        Action::Table => app.toggle_standings(),
+        Action::Log => app.toggle_log(),
+        Action::Hint => app.toggle_hint(),
+        Action::Undo => app.undo(),
+        Action::Menu => app.show_title(),
+        // the bot's or the other player's dice are not for the human to touch
+        _ if app.is_bot_turn() || app.is_remote_turn() => {}
+        Action::Roll if !app.is_rolling() => app.start_roll()?,
+        Action::Hold(index) if !app.is_rolling() => app.toggle_hold(index),
+        Action::Next if app.game.roll_count() > 0 => app.game.select_next(),
+        Action::Prev if app.game.roll_count() > 0 => app.game.select_prev(),
+        Action::Down if app.game.roll_count() > 0 => app.game.select_column(true),
+        Action::Up if app.game.roll_count() > 0 => app.game.select_column(false),
+        Action::Score => {
             if app.is_game_over() {
                 app.start_over();
-            } else if !app.is_rolling() && app.roll_count > 0 {
-                app.submit_selection()
+            } else if !app.is_rolling() {
+                app.submit_selection()?
             }
         }
         _ => {}
     }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{mode::Mode, roller::SeededRoller};
+
+    fn press(app: &mut App, code: KeyCode) {
+        handle_key_event(app, KeyEvent::from(code)).unwrap();
+    }
+
+    #[test]
+    fn test_setup_keys_type_only_names() {
+        let mut app = App::new(Box::new(SeededRoller::new(1)));
+        app.screen = Screen::Setup;
+        press(&mut app, KeyCode::Char('l'));
+        assert_ne!(app.mode, Mode::Standard);
+        assert_eq!(app.lineup[0].name, "Player 1");
+
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Char('l'));
+        assert_eq!(app.lineup[0].name, "Player 1l");
+    }
+
+    #[test]
+    fn test_letter_bound_to_menu_leaves_name_entry() {
+        let mut app = App::new(Box::new(SeededRoller::new(1)));
+        app.keymap = "menu = \"x\"".parse().unwrap();
+        app.screen = Screen::NameEntry;
+        press(&mut app, KeyCode::Char('a'));
+        assert_eq!(app.name_input, "a");
+        press(&mut app, KeyCode::Char('x'));
+        assert_eq!(app.name_input, "a");
+        assert!(app.screen == Screen::Playing);
+    }
 }
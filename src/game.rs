@@ -0,0 +1,786 @@
+pub mod mode;
+pub mod roller;
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bot::Difficulty,
+    model::{
+        die::DieFace,
+        house_rules::HouseRules,
+        player::Player,
+        roll::{AllRolls, RollType},
+        ruleset::Ruleset,
+    },
+    score_util::{calc_score, can_score},
+};
+use mode::Mode;
+use roller::DiceRoller;
+
+pub const ROLLS_PER_TURN: u8 = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TurnState {
+    /// A new turn has started and the dice have not been rolled yet.
+    AwaitingFirstRoll,
+    /// The dice have been rolled and may be held, rerolled or scored.
+    Rolling,
+    /// All rolls, saved ones included, are used up and a category has to
+    /// be scored.
+    Choosing,
+    /// Every player has filled their scorecard.
+    GameOver,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameError {
+    GameOver,
+    NotRolled,
+    NoRollsLeft,
+    NoSuchDie(usize),
+    NoSuchColumn(usize),
+    /// The ruleset's scorecard has no such category.
+    NoSuchCategory(RollType),
+    AlreadyScored(RollType),
+    /// The Joker rules require the bonus Yahtzee to be scored elsewhere.
+    JokerForbidden(RollType),
+    /// Ordered games have to score an earlier category first.
+    OutOfOrder(RollType),
+    /// Nothing has been scored since the last roll.
+    NothingToUndo,
+    /// This turn has already been undone once.
+    UndoLimitReached,
+    /// Only the player who made the last score may take it back.
+    NotYourScore,
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::GameOver => write!(f, "the game is over"),
+            GameError::NotRolled => write!(f, "the dice have not been rolled this turn"),
+            GameError::NoRollsLeft => write!(f, "no rolls left this turn"),
+            GameError::NoSuchDie(index) => write!(f, "there is no die {}", index + 1),
+            GameError::NoSuchColumn(index) => write!(f, "there is no column {}", index + 1),
+            GameError::NoSuchCategory(roll_type) => write!(f, "there is no {roll_type:?} category"),
+            GameError::AlreadyScored(roll_type) => write!(f, "{roll_type:?} is already scored"),
+            GameError::JokerForbidden(roll_type) => {
+                write!(f, "Joker rules do not allow scoring {roll_type:?}")
+            }
+            GameError::OutOfOrder(roll_type) => {
+                write!(f, "{roll_type:?} has to wait for the categories above it")
+            }
+            GameError::NothingToUndo => write!(f, "there is no score to undo"),
+            GameError::UndoLimitReached => write!(f, "this turn has already been undone"),
+            GameError::NotYourScore => write!(f, "only the player who scored can take it back"),
+        }
+    }
+}
+
+/// A step of a game, recorded so it can be replayed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Event {
+    /// The dice after a roll.
+    Roll { dice: Vec<u8> },
+    Hold { die: usize },
+    Score {
+        category: RollType,
+        points: u32,
+        /// Scorecard column, always 0 outside Triple games.
+        #[serde(default)]
+        column: usize,
+    },
+    Undo,
+    /// The player to move dropped out of the game.
+    Forfeit,
+}
+
+/// Everything a scoring decision changes, kept so it can be undone.
+#[derive(Clone)]
+struct Snapshot {
+    players: Vec<Player>,
+    current: usize,
+    dice: Vec<DieFace>,
+    roll_count: u8,
+}
+
+/// The rules of a game, independent of how it is displayed. Players take
+/// turns in order, each scoring on their own card.
+pub struct Game {
+    mode: Mode,
+    players: Vec<Player>,
+    current: usize,
+    dice: Vec<DieFace>,
+    roll_count: u8,
+    /// The state before each scoring decision, oldest first.
+    history: Vec<Snapshot>,
+    undo_count: u32,
+    one_undo_per_turn: bool,
+    /// The turn, counted by position in `history`, that was last undone.
+    last_undone: Option<usize>,
+    events: Vec<Event>,
+    /// Whether `events` go back to the start of the game.
+    recorded_from_start: bool,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Game::new()
+    }
+}
+
+impl Game {
+    /// A solitaire game.
+    pub fn new() -> Game {
+        Game::with_players(vec![Player::new("Player")])
+    }
+
+    pub fn with_players(players: Vec<Player>) -> Game {
+        assert!(!players.is_empty(), "a game needs at least one player");
+        let dice = new_dice(players[0].rolls().ruleset);
+        Game::restore(players, 0, dice, 0)
+    }
+
+    /// Continues a game from saved scorecards and dice.
+    pub fn restore(players: Vec<Player>, current: usize, dice: Vec<DieFace>, roll_count: u8) -> Game {
+        assert!(!players.is_empty(), "a game needs at least one player");
+        let recorded_from_start = !players_have_scored(&players) && roll_count == 0;
+        Game {
+            mode: Mode::Standard,
+            players,
+            current,
+            dice,
+            roll_count,
+            history: Vec::new(),
+            undo_count: 0,
+            one_undo_per_turn: false,
+            last_undone: None,
+            events: Vec::new(),
+            recorded_from_start,
+        }
+    }
+
+    /// Plays `mode`, giving every player the columns it needs.
+    pub fn with_mode(mut self, mode: Mode) -> Game {
+        self.mode = mode;
+        for player in &mut self.players {
+            let rolls = player.rolls();
+            let blank = AllRolls::with_rules(rolls.ruleset, rolls.house_rules);
+            player.columns.resize(mode.columns(), blank);
+        }
+        self
+    }
+
+    /// Plays by `ruleset`, giving every player blank scorecards and the
+    /// dice for it.
+    pub fn with_ruleset(mut self, ruleset: Ruleset) -> Game {
+        for player in &mut self.players {
+            let house_rules = player.rolls().house_rules;
+            player.columns.fill(AllRolls::with_rules(ruleset, house_rules));
+        }
+        self.dice = new_dice(ruleset);
+        self
+    }
+
+    /// Plays with `house_rules` on top of the ruleset.
+    pub fn with_house_rules(mut self, house_rules: HouseRules) -> Game {
+        for player in &mut self.players {
+            for column in &mut player.columns {
+                column.house_rules = house_rules;
+            }
+        }
+        self
+    }
+
+    /// Carries over the recording of a restored game.
+    pub fn with_events(mut self, events: Vec<Event>) -> Game {
+        self.events = events;
+        self.recorded_from_start = true;
+        self
+    }
+
+    /// Carries over how often a restored game was undone.
+    pub fn with_undo_count(mut self, undo_count: u32) -> Game {
+        self.undo_count = undo_count;
+        self
+    }
+
+    /// Allows each turn to be undone only once.
+    pub fn with_one_undo_per_turn(mut self, one_undo_per_turn: bool) -> Game {
+        self.one_undo_per_turn = one_undo_per_turn;
+        self
+    }
+
+    /// Whether any dice have been rolled this game.
+    pub fn has_started(&self) -> bool {
+        self.roll_count > 0 || players_have_scored(&self.players)
+    }
+
+    pub fn state(&self) -> TurnState {
+        if self.players.iter().all(|p| p.forfeited || p.is_complete()) {
+            TurnState::GameOver
+        } else if self.roll_count == 0 {
+            TurnState::AwaitingFirstRoll
+        } else if self.rolls_left() > 0 {
+            TurnState::Rolling
+        } else {
+            TurnState::Choosing
+        }
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn ruleset(&self) -> Ruleset {
+        self.players[0].rolls().ruleset
+    }
+
+    pub fn house_rules(&self) -> HouseRules {
+        self.players[0].rolls().house_rules
+    }
+
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    /// Lets a computer player take over `seat`, such as that of a player
+    /// who left.
+    pub fn hand_to_bot(&mut self, seat: usize, difficulty: Difficulty) {
+        self.players[seat].bot = Some(difficulty);
+    }
+
+    pub fn current_player_index(&self) -> usize {
+        self.current
+    }
+
+    pub fn current_player(&self) -> &Player {
+        &self.players[self.current]
+    }
+
+    /// Mutable access to the current player, used to move the selection cursor.
+    pub fn current_player_mut(&mut self) -> &mut Player {
+        &mut self.players[self.current]
+    }
+
+    /// The scorecard column the player whose turn it is is working on.
+    pub fn rolls(&self) -> &AllRolls {
+        let player = self.current_player();
+        &player.columns[player.active_column()]
+    }
+
+    /// Mutable access to the current scorecard column, used to move the selection cursor.
+    pub fn rolls_mut(&mut self) -> &mut AllRolls {
+        let player = &mut self.players[self.current];
+        let column = player.active_column();
+        &mut player.columns[column]
+    }
+
+    pub fn dice(&self) -> &[DieFace] {
+        &self.dice
+    }
+
+    pub fn roll_count(&self) -> u8 {
+        self.roll_count
+    }
+
+    /// Rolls the current player may make this turn, saved ones included.
+    pub fn rolls_per_turn(&self) -> u8 {
+        let rolls = self.house_rules().rolls_per_turn.unwrap_or(ROLLS_PER_TURN);
+        rolls + self.current_player().saved_rolls
+    }
+
+    pub fn rolls_left(&self) -> u8 {
+        self.rolls_per_turn().saturating_sub(self.roll_count)
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.state() == TurnState::GameOver
+    }
+
+    /// Everything that happened this game, unless it was restored from a
+    /// save made before games were recorded.
+    pub fn events(&self) -> Option<&[Event]> {
+        self.recorded_from_start.then_some(self.events.as_slice())
+    }
+
+    /// How many scoring decisions have been taken back this game.
+    pub fn undo_count(&self) -> u32 {
+        self.undo_count
+    }
+
+    /// The seat that made the score `undo` would take back.
+    pub fn last_scorer(&self) -> Option<usize> {
+        self.history.last().map(|snapshot| snapshot.current)
+    }
+
+    /// The player whose score `undo` would take back.
+    pub fn last_scored_by(&self) -> Option<&Player> {
+        self.last_scorer().map(|seat| &self.players[seat])
+    }
+
+    /// Whether `undo` would succeed for the player in `seat`.
+    pub fn can_undo(&self, seat: usize) -> Result<(), GameError> {
+        match self.state() {
+            TurnState::AwaitingFirstRoll if !self.history.is_empty() => {}
+            // the game over screen is final, and later turns have seen new dice
+            _ => return Err(GameError::NothingToUndo),
+        }
+        if self.last_scorer() != Some(seat) {
+            return Err(GameError::NotYourScore);
+        }
+        if self.one_undo_per_turn && self.last_undone == Some(self.history.len() - 1) {
+            return Err(GameError::UndoLimitReached);
+        }
+        Ok(())
+    }
+
+    /// Takes back the last scoring decision for the player in `seat` who
+    /// made it, as long as the next roll has not been made, returning the
+    /// dice and the scorecard to how they were.
+    pub fn undo(&mut self, seat: usize) -> Result<(), GameError> {
+        self.can_undo(seat)?;
+        let snapshot = self.history.pop().unwrap();
+        self.players = snapshot.players;
+        self.current = snapshot.current;
+        self.dice = snapshot.dice;
+        self.roll_count = snapshot.roll_count;
+        self.undo_count += 1;
+        self.last_undone = Some(self.history.len());
+        self.events.push(Event::Undo);
+        Ok(())
+    }
+
+    /// Rolls every die that is not held, taking the new values from `roller`.
+    pub fn roll(&mut self, roller: &mut dyn DiceRoller) -> Result<(), GameError> {
+        match self.state() {
+            TurnState::GameOver => return Err(GameError::GameOver),
+            TurnState::Choosing => return Err(GameError::NoRollsLeft),
+            TurnState::AwaitingFirstRoll | TurnState::Rolling => {}
+        }
+
+        self.roll_count += 1;
+        for die in self.dice.iter_mut().filter(|d| !d.held) {
+            die.value = roller.roll_die();
+        }
+        let dice = self.dice.iter().map(|d| d.value).collect();
+        self.events.push(Event::Roll { dice });
+        Ok(())
+    }
+
+    /// Toggles whether the die at `index` is kept for the next roll.
+    pub fn hold(&mut self, index: usize) -> Result<(), GameError> {
+        match self.state() {
+            TurnState::GameOver => return Err(GameError::GameOver),
+            TurnState::AwaitingFirstRoll => return Err(GameError::NotRolled),
+            TurnState::Rolling | TurnState::Choosing => {}
+        }
+
+        let die = self.dice.get_mut(index).ok_or(GameError::NoSuchDie(index))?;
+        die.held = !die.held;
+        self.events.push(Event::Hold { die: index });
+        Ok(())
+    }
+
+    /// Scores the current dice in `roll_type` of the column being worked on
+    /// and passes the dice to the next player.
+    pub fn score(&mut self, roll_type: RollType) -> Result<u32, GameError> {
+        self.score_in(self.current_player().active_column(), roll_type)
+    }
+
+    /// Scores the current dice in `roll_type` of scorecard column `column`.
+    pub fn score_in(&mut self, column: usize, roll_type: RollType) -> Result<u32, GameError> {
+        // score before the bonus is applied so Joker values see the current scorecard
+        let score = self.check_score(column, roll_type)?;
+        self.history.push(Snapshot {
+            players: self.players.clone(),
+            current: self.current,
+            dice: self.dice.clone(),
+            roll_count: self.roll_count,
+        });
+
+        let rolls = &mut self.players[self.current].columns[column];
+        if rolls.roll(RollType::Yahtzee).score.is_some_and(|s| s > 0) {
+            // if yahtzee already scored, always check for bonus yahtzee
+            rolls.roll_mut(RollType::Yahtzee).score = Some(calc_score(*rolls.roll(RollType::Yahtzee), rolls, &self.dice));
+        }
+        rolls.roll_mut(roll_type).score = Some(score);
+        self.events.push(Event::Score {
+            category: roll_type,
+            points: score,
+            column,
+        });
+        self.end_turn();
+        Ok(score)
+    }
+
+    /// What `score_in` would score, without scoring it.
+    pub fn check_score(&self, column: usize, roll_type: RollType) -> Result<u32, GameError> {
+        match self.state() {
+            TurnState::GameOver => return Err(GameError::GameOver),
+            TurnState::AwaitingFirstRoll => return Err(GameError::NotRolled),
+            TurnState::Rolling | TurnState::Choosing => {}
+        }
+        let rolls = self.players[self.current]
+            .columns
+            .get(column)
+            .ok_or(GameError::NoSuchColumn(column))?;
+        if !rolls.has(roll_type) {
+            return Err(GameError::NoSuchCategory(roll_type));
+        }
+        let roll = *rolls.roll(roll_type);
+        if roll.score.is_some() {
+            return Err(GameError::AlreadyScored(roll_type));
+        }
+        if self.mode == Mode::Ordered {
+            // the order decides where a Joker goes, not the Joker rules
+            if rolls.iter().find(|r| r.score.is_none()).map(|r| r.roll_type) != Some(roll_type) {
+                return Err(GameError::OutOfOrder(roll_type));
+            }
+        } else if !can_score(roll_type, rolls, &self.dice) {
+            return Err(GameError::JokerForbidden(roll_type));
+        }
+        Ok(calc_score(roll, rolls, &self.dice))
+    }
+
+    /// Takes the player to move out of the game, such as a program that
+    /// broke the rules. Their scores stand, but they rank below everyone
+    /// and get no more turns.
+    pub fn forfeit(&mut self) -> Result<(), GameError> {
+        if self.is_game_over() {
+            return Err(GameError::GameOver);
+        }
+        self.current_player_mut().forfeited = true;
+        // a forfeit is final, and so is everything before it
+        self.history.clear();
+        self.last_undone = None;
+        self.events.push(Event::Forfeit);
+        self.end_turn();
+        Ok(())
+    }
+
+    /// Moves the current player's selection to the next open slot. Ordered
+    /// games keep it on the one slot that may be scored.
+    pub fn select_next(&mut self) {
+        if self.mode != Mode::Ordered {
+            self.current_player_mut().select_next();
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        if self.mode != Mode::Ordered {
+            self.current_player_mut().select_prev();
+        }
+    }
+
+    pub fn select_column(&mut self, forward: bool) {
+        if self.mode != Mode::Ordered {
+            self.current_player_mut().select_column(forward);
+        }
+    }
+
+    /// Total score of the player whose turn it is.
+    pub fn total_score(&self) -> u32 {
+        self.current_player().total_score()
+    }
+
+    fn end_turn(&mut self) {
+        if self.ruleset().saves_rolls() {
+            let rolls_left = self.rolls_left();
+            self.current_player_mut().saved_rolls = rolls_left;
+        }
+        for die in &mut self.dice {
+            die.held = false;
+        }
+        self.roll_count = 0;
+        self.current_player_mut().clear_selection();
+        for _ in 0..self.players.len() {
+            self.current = (self.current + 1) % self.players.len();
+            if !self.current_player().forfeited {
+                break;
+            }
+        }
+    }
+}
+
+/// A fresh set of the dice `ruleset` plays with.
+fn new_dice(ruleset: Ruleset) -> Vec<DieFace> {
+    (1..=ruleset.dice_count() as u8).map(DieFace::new).collect()
+}
+
+fn players_have_scored(players: &[Player]) -> bool {
+    players
+        .iter()
+        .flat_map(|p| &p.columns)
+        .any(|rolls| rolls.iter().any(|r| r.score.is_some()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use roller::ScriptedRoller;
+
+    #[test]
+    fn test_turn_states() {
+        let mut game = Game::new();
+        let mut dice = ScriptedRoller::new([1; 15]);
+        assert_eq!(game.state(), TurnState::AwaitingFirstRoll);
+        game.roll(&mut dice).unwrap();
+        assert_eq!(game.state(), TurnState::Rolling);
+        game.roll(&mut dice).unwrap();
+        game.roll(&mut dice).unwrap();
+        assert_eq!(game.state(), TurnState::Choosing);
+        assert_eq!(game.roll(&mut dice), Err(GameError::NoRollsLeft));
+        game.score(RollType::Chance).unwrap();
+        assert_eq!(game.state(), TurnState::AwaitingFirstRoll);
+    }
+
+    #[test]
+    fn test_must_roll_before_hold_or_score() {
+        let mut game = Game::new();
+        assert_eq!(game.hold(0), Err(GameError::NotRolled));
+        assert_eq!(game.score(RollType::Chance), Err(GameError::NotRolled));
+    }
+
+    #[test]
+    fn test_held_dice_are_not_rolled() {
+        let mut game = Game::new();
+        let mut dice = ScriptedRoller::new([6, 6, 6, 6, 6, 1, 2, 3, 4, 1, 2, 3, 4]);
+        game.roll(&mut dice).unwrap();
+        game.hold(2).unwrap();
+        game.roll(&mut dice).unwrap();
+        game.roll(&mut dice).unwrap();
+        let values: Vec<u8> = game.dice().iter().map(|d| d.value).collect();
+        assert_eq!(values, vec![1, 2, 6, 3, 4]);
+        assert_eq!(game.hold(5), Err(GameError::NoSuchDie(5)));
+    }
+
+    #[test]
+    fn test_cannot_score_twice() {
+        let mut game = Game::new();
+        let mut dice = ScriptedRoller::new([1, 2, 3, 4, 6, 1, 2, 3, 4, 6]);
+        game.roll(&mut dice).unwrap();
+        assert_eq!(game.score(RollType::SmallStraight), Ok(30));
+        assert!(!game.dice().iter().any(|d| d.held));
+        game.roll(&mut dice).unwrap();
+        assert_eq!(game.score(RollType::SmallStraight), Err(GameError::AlreadyScored(RollType::SmallStraight)));
+    }
+
+    #[test]
+    fn test_bonus_yahtzee_and_joker() {
+        let mut game = Game::new();
+        let mut dice = ScriptedRoller::new([5; 15]);
+        game.roll(&mut dice).unwrap();
+        assert_eq!(game.score(RollType::Yahtzee), Ok(50));
+
+        game.roll(&mut dice).unwrap();
+        assert_eq!(game.score(RollType::FullHouse), Err(GameError::JokerForbidden(RollType::FullHouse)));
+        assert_eq!(game.score(RollType::Fives), Ok(25));
+
+        game.roll(&mut dice).unwrap();
+        assert_eq!(game.score(RollType::FullHouse), Ok(25));
+        assert_eq!(game.rolls().roll(RollType::Yahtzee).score, Some(250));
+    }
+
+    #[test]
+    fn test_scratched_yahtzee_stays_scratched() {
+        let mut game = Game::new();
+        let mut dice = ScriptedRoller::new([1, 2, 3, 4, 6, 5, 5, 5, 5, 5]);
+        game.roll(&mut dice).unwrap();
+        assert_eq!(game.score(RollType::Yahtzee), Ok(0));
+
+        // still a Joker, forced into Fives, but no bonus
+        game.roll(&mut dice).unwrap();
+        assert_eq!(game.score(RollType::Chance), Err(GameError::JokerForbidden(RollType::Chance)));
+        assert_eq!(game.score(RollType::Fives), Ok(25));
+        assert_eq!(game.rolls().roll(RollType::Yahtzee).score, Some(0));
+        assert_eq!(game.rolls().yahtzee_bonus_count(), 0);
+    }
+
+    #[test]
+    fn test_game_over_after_every_slot() {
+        let mut game = Game::new();
+        let roll_types: Vec<RollType> = game.rolls().iter().map(|r| r.roll_type).collect();
+        let mut dice = ScriptedRoller::new([1, 1, 2, 2, 3].repeat(roll_types.len()));
+        for roll_type in roll_types {
+            game.roll(&mut dice).unwrap();
+            game.score(roll_type).unwrap();
+        }
+        assert_eq!(game.state(), TurnState::GameOver);
+        assert_eq!(game.roll(&mut dice), Err(GameError::GameOver));
+        assert_eq!(game.total_score(), 2 + 4 + 3 + 9);
+    }
+
+    #[test]
+    fn test_undo_last_score() {
+        let mut game = Game::new().with_one_undo_per_turn(true);
+        let mut dice = ScriptedRoller::new([2, 2, 2, 3, 3, 1, 1, 1, 1, 1]);
+        assert_eq!(game.undo(0), Err(GameError::NothingToUndo));
+        game.roll(&mut dice).unwrap();
+        game.hold(0).unwrap();
+        game.score(RollType::Twos).unwrap();
+
+        game.undo(0).unwrap();
+        assert_eq!(game.state(), TurnState::Rolling);
+        assert_eq!(game.rolls().roll(RollType::Twos).score, None);
+        assert!(game.dice()[0].held);
+        assert_eq!(game.undo(0), Err(GameError::NothingToUndo));
+
+        game.score(RollType::FullHouse).unwrap();
+        assert_eq!(game.undo(0), Err(GameError::UndoLimitReached));
+        game.roll(&mut dice).unwrap();
+        game.score(RollType::Ones).unwrap();
+        game.undo(0).unwrap();
+        assert_eq!(game.rolls().roll(RollType::FullHouse).score, Some(25));
+        assert_eq!(game.undo_count(), 2);
+    }
+
+    #[test]
+    fn test_players_take_turns() {
+        let mut game = Game::with_players(vec![Player::new("a"), Player::new("b")]);
+        let mut dice = ScriptedRoller::new([6; 10]);
+        game.roll(&mut dice).unwrap();
+        game.score(RollType::Sixes).unwrap();
+        assert_eq!(game.current_player().name, "b");
+        assert_eq!(game.rolls().roll(RollType::Sixes).score, None);
+        game.roll(&mut dice).unwrap();
+        game.score(RollType::Chance).unwrap();
+        assert_eq!(game.current_player().name, "a");
+        assert_eq!(game.players()[0].total_score(), 30);
+        assert_eq!(game.players()[1].total_score(), 30);
+    }
+
+    #[test]
+    fn test_only_the_scorer_can_undo() {
+        let mut game = Game::with_players(vec![Player::new("a"), Player::new("b")]);
+        let mut dice = ScriptedRoller::new([3; 10]);
+        game.roll(&mut dice).unwrap();
+        game.score(RollType::Threes).unwrap();
+        assert_eq!(game.last_scorer(), Some(0));
+        assert_eq!(game.undo(1), Err(GameError::NotYourScore));
+        game.undo(0).unwrap();
+        assert_eq!(game.current_player().name, "a");
+
+        game.score(RollType::Chance).unwrap();
+        game.roll(&mut dice).unwrap();
+        // once b has rolled, the score stands for a as well
+        assert_eq!(game.undo(0), Err(GameError::NothingToUndo));
+        game.score(RollType::Threes).unwrap();
+        assert_eq!(game.undo(0), Err(GameError::NotYourScore));
+        assert_eq!(game.can_undo(1), Ok(()));
+    }
+
+    #[test]
+    #[should_panic(expected = "a game needs at least one player")]
+    fn test_game_needs_a_player() {
+        Game::with_players(Vec::new());
+    }
+
+    #[test]
+    fn test_triple_columns() {
+        let mut game = Game::new().with_mode(Mode::Triple);
+        let mut dice = ScriptedRoller::new([4; 10]);
+        game.roll(&mut dice).unwrap();
+        assert_eq!(game.score_in(2, RollType::Yahtzee), Ok(50));
+        game.roll(&mut dice).unwrap();
+        assert_eq!(game.score_in(3, RollType::Chance), Err(GameError::NoSuchColumn(3)));
+        // the bonus and joker only apply in the column holding the Yahtzee
+        assert_eq!(game.score_in(0, RollType::FullHouse), Ok(0));
+        assert_eq!(game.players()[0].columns[2].roll(RollType::Yahtzee).score, Some(50));
+        assert_eq!(game.total_score(), 150);
+        assert!(matches!(game.events().unwrap()[1], Event::Score { column: 2, .. }));
+    }
+
+    #[test]
+    fn test_ordered_fills_top_to_bottom() {
+        let mut game = Game::new().with_mode(Mode::Ordered);
+        let mut dice = ScriptedRoller::new([5; 15]);
+        game.roll(&mut dice).unwrap();
+        assert_eq!(game.score(RollType::Fives), Err(GameError::OutOfOrder(RollType::Fives)));
+        assert_eq!(game.score(RollType::Ones), Ok(0));
+        game.roll(&mut dice).unwrap();
+        game.current_player_mut().select_next();
+        game.select_next();
+        assert!(game.rolls().roll(RollType::Twos).selected);
+        assert_eq!(game.score(RollType::Twos), Ok(0));
+    }
+
+    #[test]
+    fn test_forfeit_skips_the_player() {
+        let players = ["a", "b", "c"].map(Player::new).to_vec();
+        let mut game = Game::with_players(players);
+        let mut dice = ScriptedRoller::new([2; 200]);
+        game.roll(&mut dice).unwrap();
+        game.score(RollType::Twos).unwrap();
+        game.forfeit().unwrap();
+        assert_eq!(game.current_player_index(), 2);
+        assert_eq!(game.can_undo(0), Err(GameError::NothingToUndo));
+        game.roll(&mut dice).unwrap();
+        game.score(RollType::Twos).unwrap();
+        assert_eq!(game.current_player_index(), 0);
+        while !game.is_game_over() {
+            game.roll(&mut dice).unwrap();
+            let open = game.rolls().iter().find(|r| r.score.is_none()).unwrap().roll_type;
+            game.score(open).unwrap();
+            assert_ne!(game.current_player_index(), 1);
+        }
+        assert!(game.players()[1].rolls().iter().all(|r| r.score.is_none()));
+        assert_eq!(game.events().unwrap()[2], Event::Forfeit);
+    }
+
+    #[test]
+    fn test_maxi_saves_unused_rolls() {
+        let mut game = Game::new().with_ruleset(Ruleset::Maxi);
+        assert_eq!(game.dice().len(), 6);
+        let mut dice = ScriptedRoller::new([1; 60]);
+        game.roll(&mut dice).unwrap();
+        game.score(RollType::Ones).unwrap();
+        assert_eq!(game.rolls_per_turn(), 5);
+        for _ in 0..5 {
+            game.roll(&mut dice).unwrap();
+        }
+        assert_eq!(game.roll(&mut dice), Err(GameError::NoRollsLeft));
+        game.score(RollType::Chance).unwrap();
+        assert_eq!(game.rolls_per_turn(), 3);
+    }
+
+    #[test]
+    fn test_house_rules_set_rolls_per_turn() {
+        let house_rules = HouseRules {
+            rolls_per_turn: Some(2),
+            ..HouseRules::default()
+        };
+        let mut game = Game::new().with_house_rules(house_rules).with_mode(Mode::Triple);
+        assert!(game.current_player().columns.iter().all(|c| c.house_rules == house_rules));
+        let mut dice = ScriptedRoller::new([1; 10]);
+        game.roll(&mut dice).unwrap();
+        game.roll(&mut dice).unwrap();
+        assert_eq!(game.roll(&mut dice), Err(GameError::NoRollsLeft));
+    }
+
+    #[test]
+    fn test_house_rule_jokers_in_yacht() {
+        let house_rules = HouseRules {
+            jokers: Some(true),
+            ..HouseRules::default()
+        };
+        let mut game = Game::new().with_ruleset(Ruleset::Yacht).with_house_rules(house_rules);
+        let mut dice = ScriptedRoller::new([4; 20]);
+        game.roll(&mut dice).unwrap();
+        assert_eq!(game.score(RollType::Yahtzee), Ok(50));
+        game.roll(&mut dice).unwrap();
+        assert_eq!(game.score(RollType::FullHouse), Err(GameError::JokerForbidden(RollType::FullHouse)));
+        assert_eq!(game.score(RollType::Fours), Ok(20));
+        // Jokers take Yacht's values: 30 for either straight and the dice for a full house
+        game.roll(&mut dice).unwrap();
+        assert_eq!(game.score(RollType::Ones), Err(GameError::JokerForbidden(RollType::Ones)));
+        assert_eq!(game.score(RollType::LargeStraight), Ok(30));
+        game.roll(&mut dice).unwrap();
+        assert_eq!(game.score(RollType::FullHouse), Ok(20));
+        // and Yacht still has no bonus Yahtzees
+        assert_eq!(game.rolls().roll(RollType::Yahtzee).score, Some(50));
+    }
+}
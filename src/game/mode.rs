@@ -0,0 +1,51 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// How a game is played, on top of the scoring rules.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    /// One scorecard column per player.
+    #[default]
+    Standard,
+    /// Three columns counting once, twice and three times, any of which
+    /// a turn may be scored in.
+    Triple,
+    /// Categories have to be filled top to bottom, in scorecard order.
+    Ordered,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 3] = [Mode::Standard, Mode::Triple, Mode::Ordered];
+
+    /// Number of scorecard columns each player fills.
+    pub fn columns(self) -> usize {
+        match self {
+            Mode::Standard | Mode::Ordered => 1,
+            Mode::Triple => 3,
+        }
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Mode::Standard => "Standard",
+            Mode::Triple => "Triple",
+            Mode::Ordered => "Ordered",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Mode, String> {
+        Mode::ALL
+            .into_iter()
+            .find(|m| m.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown mode: {s}"))
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::VecDeque;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Source of die values for a game.
+pub trait DiceRoller {
+    /// Returns a value from 1 to 6.
+    fn roll_die(&mut self) -> u8;
+}
+
+/// Rolls dice from a seeded RNG, so the same seed replays the same dice.
+/// ChaCha8 rolls the same on every platform and version of `rand`, which
+/// `StdRng` does not promise, so daily challenges match for everyone.
+pub struct SeededRoller {
+    rng: ChaCha8Rng,
+}
+
+impl SeededRoller {
+    pub fn new(seed: u64) -> SeededRoller {
+        SeededRoller {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl DiceRoller for SeededRoller {
+    fn roll_die(&mut self) -> u8 {
+        self.rng.random_range(1..=6)
+    }
+}
+
+/// Rolls a fixed sequence of values, for tests and replays.
+pub struct ScriptedRoller {
+    values: VecDeque<u8>,
+}
+
+impl ScriptedRoller {
+    pub fn new(values: impl IntoIterator<Item = u8>) -> ScriptedRoller {
+        ScriptedRoller {
+            values: values.into_iter().collect(),
+        }
+    }
+}
+
+impl DiceRoller for ScriptedRoller {
+    fn roll_die(&mut self) -> u8 {
+        self.values.pop_front().expect("scripted roller ran out of values")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_dice() {
+        let mut a = SeededRoller::new(42);
+        let mut b = SeededRoller::new(42);
+        let a_values: Vec<u8> = (0..50).map(|_| a.roll_die()).collect();
+        let b_values: Vec<u8> = (0..50).map(|_| b.roll_die()).collect();
+        assert_eq!(a_values, b_values);
+        assert!(a_values.iter().all(|v| (1..=6).contains(v)));
+    }
+
+    #[test]
+    fn test_seeded_dice_are_pinned() {
+        let mut roller = SeededRoller::new(20250307);
+        let values: Vec<u8> = (0..10).map(|_| roller.roll_die()).collect();
+        // a daily challenge has to roll these dice wherever it is played
+        assert_eq!(values, [6, 5, 1, 3, 1, 6, 3, 2, 1, 3]);
+    }
+
+    #[test]
+    fn test_scripted_values_in_order() {
+        let mut roller = ScriptedRoller::new([3, 1, 6]);
+        assert_eq!(roller.roll_die(), 3);
+        assert_eq!(roller.roll_die(), 1);
+        assert_eq!(roller.roll_die(), 6);
+    }
+}
@@ -0,0 +1,240 @@
+use std::{fmt, fs, io, path::PathBuf};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::{Game, mode::Mode},
+    model::{house_rules::HouseRules, player::Player, ruleset::Ruleset},
+    storage,
+};
+
+/// Number of scores kept in each table.
+pub const MAX_ENTRIES: usize = 10;
+
+const HIGH_SCORES_VERSION: u32 = 1;
+
+/// Which table a score goes in: games only rank against games played the
+/// same way, house rules included.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Variant {
+    pub mode: Mode,
+    pub ruleset: Ruleset,
+    pub house_rules: HouseRules,
+}
+
+impl Variant {
+    pub fn of(game: &Game) -> Variant {
+        Variant {
+            mode: game.mode(),
+            ruleset: game.ruleset(),
+            house_rules: game.house_rules(),
+        }
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.mode != Mode::Standard {
+            write!(f, "{} ", self.mode)?;
+        }
+        f.write_str(self.ruleset.game_name())?;
+        if !self.house_rules.is_default() {
+            f.write_str(" (house rules)")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HighScore {
+    /// Older tables only held standard games.
+    #[serde(default)]
+    pub mode: Mode,
+    #[serde(default)]
+    pub ruleset: Ruleset,
+    #[serde(default)]
+    pub house_rules: HouseRules,
+    pub name: String,
+    pub date: NaiveDate,
+    pub total: u32,
+    pub upper_bonus: u32,
+    pub yahtzee_bonus_count: u32,
+}
+
+impl HighScore {
+    /// An entry for a player's finished scorecard, dated today.
+    pub fn new(name: &str, player: &Player, variant: Variant) -> HighScore {
+        HighScore {
+            mode: variant.mode,
+            ruleset: variant.ruleset,
+            house_rules: variant.house_rules,
+            name: name.to_string(),
+            date: chrono::Local::now().date_naive(),
+            total: player.total_score(),
+            upper_bonus: player.upper_bonus(),
+            yahtzee_bonus_count: player.yahtzee_bonus_count(),
+        }
+    }
+
+    pub fn variant(&self) -> Variant {
+        Variant {
+            mode: self.mode,
+            ruleset: self.ruleset,
+            house_rules: self.house_rules,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct HighScores {
+    version: u32,
+    pub entries: Vec<HighScore>,
+}
+
+impl Default for HighScores {
+    fn default() -> Self {
+        HighScores::new()
+    }
+}
+
+impl HighScores {
+    pub fn new() -> HighScores {
+        HighScores {
+            version: HIGH_SCORES_VERSION,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Loads the table from disk, starting a new one if there is none.
+    pub fn load() -> HighScores {
+        high_scores_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str::<HighScores>(&contents).ok())
+            .filter(|scores| scores.version == HIGH_SCORES_VERSION)
+            .unwrap_or_default()
+    }
+
+    pub fn store(&self) -> io::Result<()> {
+        let Some(path) = high_scores_path() else {
+            return Ok(());
+        };
+        let json = serde_json::to_vec_pretty(self).map_err(io::Error::other)?;
+        storage::write_atomic(&path, &json)
+    }
+
+    /// The table of `variant`, best first.
+    pub fn table(&self, variant: Variant) -> Vec<HighScore> {
+        self.entries.iter().filter(|e| e.variant() == variant).cloned().collect()
+    }
+
+    /// Whether `total` would make it into the table of `variant`.
+    pub fn qualifies(&self, variant: Variant, total: u32) -> bool {
+        let table = self.table(variant);
+        table.len() < MAX_ENTRIES || table.iter().any(|e| total > e.total)
+    }
+
+    /// Adds `entry` in score order and returns its position in its
+    /// variant's table, if it made the table.
+    pub fn insert(&mut self, entry: HighScore) -> Option<usize> {
+        let variant = entry.variant();
+        // ties go below existing scores, the earlier score keeps its place
+        let index = self.entries.iter().position(|e| entry.total > e.total)
+            .unwrap_or(self.entries.len());
+        let position = self.entries[..index].iter().filter(|e| e.variant() == variant).count();
+        if position >= MAX_ENTRIES {
+            return None;
+        }
+        self.entries.insert(index, entry);
+        // drop whatever fell off the end of the variant's table
+        let dropped = self.entries.iter().enumerate().filter(|(_, e)| e.variant() == variant).nth(MAX_ENTRIES);
+        if let Some((i, _)) = dropped {
+            self.entries.remove(i);
+        }
+        Some(position)
+    }
+}
+
+fn high_scores_path() -> Option<PathBuf> {
+    storage::data_dir().map(|dir| dir.join("high_scores.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, total: u32) -> HighScore {
+        entry_in(Variant::default(), name, total)
+    }
+
+    fn entry_in(variant: Variant, name: &str, total: u32) -> HighScore {
+        HighScore {
+            mode: variant.mode,
+            ruleset: variant.ruleset,
+            house_rules: variant.house_rules,
+            name: name.to_string(),
+            date: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            total,
+            upper_bonus: 0,
+            yahtzee_bonus_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_insert_keeps_order_and_limit() {
+        let mut scores = HighScores::new();
+        for total in (1..=MAX_ENTRIES as u32).map(|n| n * 10) {
+            assert!(scores.qualifies(Variant::default(), total));
+            scores.insert(entry("a", total));
+        }
+        assert!(!scores.qualifies(Variant::default(), 10));
+        assert_eq!(scores.insert(entry("b", 10)), None);
+
+        assert_eq!(scores.insert(entry("c", 55)), Some(5));
+        assert_eq!(scores.entries.len(), MAX_ENTRIES);
+        assert_eq!(scores.entries[0].total, 100);
+        assert_eq!(scores.entries[MAX_ENTRIES - 1].total, 20);
+    }
+
+    #[test]
+    fn test_ties_rank_below_existing() {
+        let mut scores = HighScores::new();
+        scores.insert(entry("first", 200));
+        assert_eq!(scores.insert(entry("second", 200)), Some(1));
+        assert_eq!(scores.entries[0].name, "first");
+    }
+
+    #[test]
+    fn test_variants_have_separate_tables() {
+        let mut scores = HighScores::new();
+        for total in (1..=MAX_ENTRIES as u32).map(|n| n * 100) {
+            scores.insert(entry("a", total));
+        }
+        let triple = Variant {
+            mode: Mode::Triple,
+            ..Variant::default()
+        };
+        let yacht = Variant {
+            ruleset: Ruleset::Yacht,
+            ..Variant::default()
+        };
+        let house = Variant {
+            house_rules: HouseRules {
+                rolls_per_turn: Some(4),
+                ..HouseRules::default()
+            },
+            ..Variant::default()
+        };
+        assert!(scores.qualifies(triple, 5));
+        assert_eq!(scores.insert(entry_in(triple, "b", 450)), Some(0));
+        assert_eq!(scores.insert(entry_in(yacht, "d", 150)), Some(0));
+        assert_eq!(scores.insert(entry_in(house, "e", 50)), Some(0));
+        assert_eq!(scores.insert(entry("c", 450)), Some(6));
+        assert_eq!(scores.table(Variant::default()).len(), MAX_ENTRIES);
+        assert_eq!(scores.table(triple)[0].name, "b");
+        assert_eq!(scores.table(yacht)[0].name, "d");
+        assert_eq!(scores.table(house)[0].name, "e");
+        assert_eq!(scores.entries.len(), MAX_ENTRIES + 3);
+        assert_eq!(house.to_string(), "Yahtzee (house rules)");
+    }
+}
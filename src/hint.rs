@@ -0,0 +1,147 @@
+use crate::{
+    game::{Game, TurnState},
+    model::{player::Player, roll::RollType},
+    solver::{Solver, SolverState},
+};
+
+/// What the solver recommends for the current roll, and what the player's
+/// own choice gives up compared to it.
+pub struct Hint {
+    /// Expected final score when playing optimally from here.
+    pub expected: f32,
+    /// Dice to hold, when rerolling beats scoring now.
+    pub hold: Option<Vec<bool>>,
+    /// Category to score, when scoring now is the best play.
+    pub category: Option<RollType>,
+    /// Expected points lost by the current hold or selected category.
+    pub loss: Option<f32>,
+}
+
+impl Hint {
+    /// In Triple games the hint is for the column being worked on, valuing
+    /// the other columns as if each were played on its own.
+    pub fn new(solver: &Solver, game: &Game) -> Hint {
+        let player = game.current_player();
+        let column = player.active_column();
+        let weight = Player::weight(column) as f32;
+        let others: f32 = player
+            .columns
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != column)
+            .map(|(i, rolls)| Player::weight(i) as f32 * solver.expected_value(SolverState::from_rolls(rolls)))
+            .sum();
+        let state = SolverState::from_rolls(game.rolls());
+        let mut hint = Hint {
+            expected: game.total_score() as f32 + others,
+            hold: None,
+            category: None,
+            loss: None,
+        };
+
+        match game.state() {
+            TurnState::GameOver => return hint,
+            TurnState::AwaitingFirstRoll => {
+                hint.expected += weight * solver.expected_value(state);
+                return hint;
+            }
+            TurnState::Rolling | TurnState::Choosing => {}
+        }
+
+        let dice: Vec<u8> = game.dice().iter().map(|d| d.value).collect();
+        let categories = solver.category_values(state, &dice);
+        let Some((best_category, category_value)) = solver.best_category(state, &dice) else {
+            return hint;
+        };
+        let rolls_left = game.rolls_left();
+
+        let mut best = category_value;
+        hint.category = Some(best_category);
+        if rolls_left > 0 {
+            let (hold, hold_value) = solver.best_hold(state, &dice, rolls_left);
+            if hold_value > category_value && !hold.iter().all(|h| *h) {
+                best = hold_value;
+                hint.hold = Some(hold);
+                hint.category = None;
+            }
+        }
+        hint.expected += weight * best;
+
+        let current = match game.rolls().iter().find(|r| r.selected) {
+            Some(selected) => categories
+                .iter()
+                .find(|(roll_type, _)| *roll_type == selected.roll_type)
+                .map(|(_, value)| *value),
+            None if rolls_left > 0 => {
+                let held: Vec<bool> = game.dice().iter().map(|d| d.held).collect();
+                Some(solver.hold_value(state, &dice, &held, rolls_left))
+            }
+            None => None,
+        };
+        hint.loss = current.map(|value| weight * (best - value).max(0.0));
+        hint
+    }
+}
+
+/// What a hint is worked out from, to tell when the game has moved on and
+/// it has to be worked out again.
+#[derive(PartialEq)]
+pub struct HintKey {
+    player: usize,
+    /// Every box of the player's columns, scored and selected.
+    boxes: Vec<(Option<u32>, bool)>,
+    /// The dice and whether each is held.
+    dice: Vec<(u8, bool)>,
+    roll_count: u8,
+    rolls_left: u8,
+}
+
+impl HintKey {
+    pub fn of(game: &Game) -> HintKey {
+        HintKey {
+            player: game.current_player_index(),
+            boxes: game
+                .current_player()
+                .columns
+                .iter()
+                .flat_map(|rolls| rolls.iter().map(|r| (r.score, r.selected)))
+                .collect(),
+            dice: game.dice().iter().map(|d| (d.value, d.held)).collect(),
+            roll_count: game.roll_count(),
+            rolls_left: game.rolls_left(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        game::roller::ScriptedRoller,
+        model::{player::Player, roll::AllRolls},
+    };
+
+    #[test]
+    fn test_hint_for_last_chance() {
+        let mut rolls = AllRolls::new();
+        for roll_type in RollType::STANDARD.iter().filter(|r| **r != RollType::Chance) {
+            rolls.roll_mut(*roll_type).score = Some(0);
+        }
+        let mut player = Player::new("a");
+        player.columns = vec![rolls];
+        let mut game = Game::with_players(vec![player]);
+        game.roll(&mut ScriptedRoller::new([6, 6, 5, 1, 2])).unwrap();
+        let solver = Solver::build_levels(1);
+
+        let hint = Hint::new(&solver, &game);
+        assert_eq!(hint.hold, Some(vec![true, true, true, false, false]));
+        assert!(hint.loss.unwrap() > 0.0);
+
+        for i in 0..3 {
+            game.hold(i).unwrap();
+        }
+        let hint = Hint::new(&solver, &game);
+        assert_eq!(hint.loss, Some(0.0));
+        assert!(hint.expected > 20.0);
+    }
+}
@@ -0,0 +1,343 @@
+use std::{fmt, fs, io, path::PathBuf, str::FromStr};
+
+use crossterm::event::KeyCode;
+
+use crate::storage;
+
+/// Most dice a Hold action can name, one per digit key.
+pub const MAX_HOLD_KEYS: usize = 9;
+
+/// Something a key does during play, in the menus or in replays.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Roll,
+    /// Holds or releases the die at this index.
+    Hold(usize),
+    /// Moves the selection to the next category.
+    Next,
+    Prev,
+    /// Moves the selection to the column above, in Triple games, or to
+    /// the row above in menus.
+    Up,
+    Down,
+    /// Scores the selected box, and chooses or confirms in menus.
+    Score,
+    Undo,
+    Hint,
+    Table,
+    Log,
+    /// Goes to the menu, and back from the screens it leads to.
+    Menu,
+    Quit,
+    /// Adds a player on the New Game screen.
+    AddPlayer,
+    RemovePlayer,
+    /// Pauses or resumes a replay.
+    Pause,
+    /// Speeds up a replay.
+    Faster,
+    Slower,
+}
+
+impl Action {
+    /// Every action, in the order the defaults list them.
+    pub fn all() -> impl Iterator<Item = Action> {
+        [Action::Roll]
+            .into_iter()
+            .chain((0..MAX_HOLD_KEYS).map(Action::Hold))
+            .chain([
+                Action::Next,
+                Action::Prev,
+                Action::Up,
+                Action::Down,
+                Action::Score,
+                Action::Undo,
+                Action::Hint,
+                Action::Table,
+                Action::Log,
+                Action::Menu,
+                Action::Quit,
+                Action::AddPlayer,
+                Action::RemovePlayer,
+                Action::Pause,
+                Action::Faster,
+                Action::Slower,
+            ])
+    }
+
+    fn default_keys(self) -> Vec<KeyCode> {
+        match self {
+            Action::Roll => vec![KeyCode::Char('r')],
+            Action::Hold(i) => vec![KeyCode::Char(char::from(b'1' + i as u8))],
+            Action::Next => vec![KeyCode::Right, KeyCode::Char('l')],
+            Action::Prev => vec![KeyCode::Left, KeyCode::Char('h')],
+            Action::Up => vec![KeyCode::Up, KeyCode::Char('k')],
+            Action::Down => vec![KeyCode::Down, KeyCode::Char('j')],
+            Action::Score => vec![KeyCode::Enter],
+            Action::Undo => vec![KeyCode::Char('u')],
+            Action::Hint => vec![KeyCode::Char('?')],
+            Action::Table => vec![KeyCode::Char('s')],
+            Action::Log => vec![KeyCode::Char('g')],
+            Action::Menu => vec![KeyCode::Esc],
+            Action::Quit => vec![KeyCode::Char('q')],
+            Action::AddPlayer => vec![KeyCode::Tab],
+            Action::RemovePlayer => vec![KeyCode::Delete],
+            Action::Pause => vec![KeyCode::Char(' ')],
+            Action::Faster => vec![KeyCode::Char('+'), KeyCode::Char('=')],
+            Action::Slower => vec![KeyCode::Char('-')],
+        }
+    }
+}
+
+/// The name an action has in `keys.toml`, such as `roll` or `hold_1`.
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Action::Roll => "roll",
+            Action::Hold(i) => return write!(f, "hold_{}", i + 1),
+            Action::Next => "next",
+            Action::Prev => "prev",
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::Score => "score",
+            Action::Undo => "undo",
+            Action::Hint => "hint",
+            Action::Table => "table",
+            Action::Log => "log",
+            Action::Menu => "menu",
+            Action::Quit => "quit",
+            Action::AddPlayer => "add_player",
+            Action::RemovePlayer => "remove_player",
+            Action::Pause => "pause",
+            Action::Faster => "faster",
+            Action::Slower => "slower",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Action, String> {
+        Action::all()
+            .find(|a| a.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown action: {s}"))
+    }
+}
+
+/// Which keys do what, read from `keys.toml`. Each action can have several
+/// keys; actions left out keep their default keys, less any taken by
+/// another action.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<KeyCode>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap {
+            bindings: Action::all().map(|a| (a, a.default_keys())).collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Reads the key bindings from the config directory. A missing
+    /// `keys.toml` means the default keys.
+    pub fn load() -> io::Result<Keymap> {
+        match keys_path().map(fs::read_to_string) {
+            Some(Ok(contents)) => contents
+                .parse()
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+            Some(Err(err)) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(Keymap::default()),
+        }
+    }
+
+    /// The action `code` is bound to, if any.
+    pub fn action(&self, code: KeyCode) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.contains(&code))
+            .map(|(action, _)| *action)
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map_or(&[], |(_, keys)| keys)
+    }
+
+    /// How the first key of `action` is shown, such as "r" or "CR".
+    pub fn label(&self, action: Action) -> Option<String> {
+        self.keys(action).first().map(|key| key_label(*key))
+    }
+
+    /// The first keys of `actions` shown together, such as "←→" for arrows
+    /// or "a/d" for anything else. Unbound actions are left out.
+    pub fn labels(&self, actions: &[Action]) -> Option<String> {
+        let labels: Vec<String> = actions.iter().filter_map(|a| self.label(*a)).collect();
+        let arrows = labels.iter().all(|label| ["←", "→", "↑", "↓"].contains(&label.as_str()));
+        Some(labels.join(if arrows { "" } else { "/" })).filter(|label| !label.is_empty())
+    }
+
+    /// The keys holding the first `dice` dice, as a range such as "1-5"
+    /// when they follow on from each other.
+    pub fn hold_label(&self, dice: usize) -> Option<String> {
+        let keys: Vec<KeyCode> = (0..dice.min(MAX_HOLD_KEYS))
+            .filter_map(|i| self.keys(Action::Hold(i)).first().copied())
+            .collect();
+        let in_a_row = keys.len() > 1
+            && keys.windows(2).all(|pair| match pair {
+                [KeyCode::Char(a), KeyCode::Char(b)] => u32::from(*a) + 1 == u32::from(*b),
+                _ => false,
+            });
+        if in_a_row {
+            Some(format!("{}-{}", key_label(keys[0]), key_label(keys[keys.len() - 1])))
+        } else {
+            Some(keys.into_iter().map(key_label).collect::<String>()).filter(|label| !label.is_empty())
+        }
+    }
+}
+
+impl FromStr for Keymap {
+    type Err = String;
+
+    /// Parses bindings such as `roll = ["r", "Space"]` or `quit = "x"`.
+    fn from_str(s: &str) -> Result<Keymap, String> {
+        let table: toml::Table = toml::from_str(s).map_err(|err| err.message().to_string())?;
+        let mut bound: Vec<(Action, Vec<KeyCode>)> = Vec::new();
+        for (name, value) in table {
+            let action: Action = name.parse()?;
+            let names = match value {
+                toml::Value::String(key) => vec![key],
+                toml::Value::Array(keys) => keys
+                    .into_iter()
+                    .map(|key| match key {
+                        toml::Value::String(key) => Ok(key),
+                        _ => Err(format!("{name} needs a key or a list of keys")),
+                    })
+                    .collect::<Result<_, _>>()?,
+                _ => return Err(format!("{name} needs a key or a list of keys")),
+            };
+            let keys = names.iter().map(|key| parse_key(key)).collect::<Result<Vec<_>, _>>()?;
+            for key in &keys {
+                if let Some((other, _)) = bound.iter().find(|(_, other_keys)| other_keys.contains(key)) {
+                    return Err(format!("{} is bound to both {other} and {action}", key_label(*key)));
+                }
+            }
+            bound.push((action, keys));
+        }
+
+        let mut keymap = Keymap::default();
+        for (action, keys) in &mut keymap.bindings {
+            match bound.iter().find(|(a, _)| a == action) {
+                Some((_, bound_keys)) => keys.clone_from(bound_keys),
+                None => keys.retain(|key| !bound.iter().any(|(_, bound_keys)| bound_keys.contains(key))),
+            }
+        }
+        Ok(keymap)
+    }
+}
+
+/// Reads a key as written in `keys.toml`: a single character or a name
+/// such as `Enter`, `Space` or `Left`.
+fn parse_key(name: &str) -> Result<KeyCode, String> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(KeyCode::Char(c));
+    }
+    let key = match name.to_ascii_lowercase().as_str() {
+        "space" => KeyCode::Char(' '),
+        "enter" | "cr" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        other => match other.strip_prefix('f').and_then(|n| n.parse().ok()) {
+            Some(n @ 1..=12) => KeyCode::F(n),
+            _ => return Err(format!("unknown key: {name}")),
+        },
+    };
+    Ok(key)
+}
+
+/// How a key is shown in the footer.
+pub fn key_label(key: KeyCode) -> String {
+    match key {
+        KeyCode::Char(' ') => String::from("space"),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Enter => String::from("CR"),
+        KeyCode::Left => String::from("←"),
+        KeyCode::Right => String::from("→"),
+        KeyCode::Up => String::from("↑"),
+        KeyCode::Down => String::from("↓"),
+        KeyCode::Delete => String::from("Del"),
+        KeyCode::Backspace => String::from("BS"),
+        KeyCode::PageUp => String::from("PgUp"),
+        KeyCode::PageDown => String::from("PgDn"),
+        KeyCode::F(n) => format!("F{n}"),
+        other => format!("{other:?}"),
+    }
+}
+
+fn keys_path() -> Option<PathBuf> {
+    storage::config_dir().map(|dir| dir.join("keys.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_keys() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.action(KeyCode::Char('r')), Some(Action::Roll));
+        assert_eq!(keymap.action(KeyCode::Char('3')), Some(Action::Hold(2)));
+        assert_eq!(keymap.action(KeyCode::Char('l')), Some(Action::Next));
+        assert_eq!(keymap.action(KeyCode::Char('x')), None);
+        assert_eq!(keymap.labels(&[Action::Prev, Action::Next]).as_deref(), Some("←→"));
+        assert_eq!(keymap.hold_label(5).as_deref(), Some("1-5"));
+        assert_eq!(keymap.label(Action::Score).as_deref(), Some("CR"));
+        assert_eq!(keymap.action(KeyCode::Tab), Some(Action::AddPlayer));
+        assert_eq!(keymap.labels(&[Action::Slower, Action::Faster]).as_deref(), Some("-/+"));
+        // every default key does one thing only
+        let keys: Vec<KeyCode> = Action::all().flat_map(|a| keymap.keys(a).to_vec()).collect();
+        assert!(keys.iter().all(|key| keys.iter().filter(|k| *k == key).count() == 1));
+    }
+
+    #[test]
+    fn test_parse_keymap() {
+        let keymap: Keymap = "roll = [\"Space\", \"r\"]\nhold_1 = \"a\"\nquit = \"l\"\nnext = [\"d\"]\n"
+            .parse()
+            .unwrap();
+        assert_eq!(keymap.keys(Action::Roll), [KeyCode::Char(' '), KeyCode::Char('r')]);
+        assert_eq!(keymap.action(KeyCode::Char('a')), Some(Action::Hold(0)));
+        assert_eq!(keymap.action(KeyCode::Char('1')), None);
+        // a key taken by another action no longer does what it did
+        assert_eq!(keymap.action(KeyCode::Char('l')), Some(Action::Quit));
+        assert_eq!(keymap.keys(Action::Next), [KeyCode::Char('d')]);
+        assert_eq!(keymap.keys(Action::Prev), [KeyCode::Left, KeyCode::Char('h')]);
+        assert_eq!(keymap.labels(&[Action::Prev, Action::Next]).as_deref(), Some("←/d"));
+        assert_eq!(keymap.label(Action::Roll).as_deref(), Some("space"));
+        assert_eq!(keymap.hold_label(3).as_deref(), Some("a23"));
+
+        assert_eq!("jump = \"j\"".parse::<Keymap>(), Err(String::from("unknown action: jump")));
+        assert_eq!("roll = \"Ctrl\"".parse::<Keymap>(), Err(String::from("unknown key: Ctrl")));
+        assert_eq!("roll = 1".parse::<Keymap>(), Err(String::from("roll needs a key or a list of keys")));
+        assert_eq!(
+            "roll = \"x\"\nundo = \"x\"".parse::<Keymap>(),
+            Err(String::from("x is bound to both roll and undo"))
+        );
+    }
+}
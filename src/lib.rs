@@ -0,0 +1,22 @@
+pub mod app;
+pub mod bot;
+pub mod cli;
+pub mod components;
+pub mod daily;
+pub mod event;
+pub mod game;
+pub mod high_scores;
+pub mod hint;
+pub mod keymap;
+pub mod model;
+pub mod net;
+pub mod replay;
+pub mod save;
+pub mod settings;
+pub mod score_util;
+pub mod sim;
+pub mod solver;
+pub mod stats;
+pub mod storage;
+pub mod theme;
+pub mod tournament;
@@ -1,18 +1,98 @@
-use std::io;
+use std::{io, net::TcpListener, process};
 
-mod app;
-mod event;
-mod components;
-mod model;
-mod theme;
-mod score_util;
-
-use app::App;
+use rahtzee::{
+    app::App,
+    bot::external,
+    cli::{self, Args},
+    daily::DailyResults,
+    game::roller::SeededRoller,
+    high_scores::HighScores,
+    keymap::Keymap,
+    model::{house_rules::HouseRules, player::Player},
+    net::{Guest, Host, JOIN_TIMEOUT},
+    replay::Replay,
+    save,
+    settings::Settings,
+    stats::Stats,
+};
 
 fn main() -> io::Result<()> {
+    let args = Args::parse().unwrap_or_else(|err| {
+        eprintln!("{err}\n{}", cli::USAGE);
+        process::exit(2);
+    });
+    let seed = args.seed.unwrap_or_else(rand::random);
+
+    let mut app = App::new(Box::new(SeededRoller::new(seed)));
+    app.seed = Some(seed);
+    app.mode = args.mode;
+    app.ruleset = args.ruleset;
+    app.house_rules = HouseRules::load(args.rules.as_deref()).unwrap_or_else(|err| {
+        eprintln!("cannot read house rules: {err}");
+        process::exit(1);
+    });
+    if let Some(path) = &args.replay {
+        let replay = Replay::load(path).unwrap_or_else(|err| {
+            eprintln!("cannot play {}: {err}", path.display());
+            process::exit(1);
+        });
+        app.start_replay(replay);
+    } else if args.host.is_none() && args.join.is_none() && args.watch.is_none() {
+        app.saved_game = save::load();
+    }
+    app.high_scores = HighScores::load();
+    app.daily_results = DailyResults::load();
+    app.stats = Stats::load();
+    app.settings = Settings::load();
+    app.settings.one_undo_per_turn |= args.one_undo_per_turn;
+    app.show_hint = app.settings.hints;
+    app.keymap = Keymap::load().unwrap_or_else(|err| {
+        eprintln!("cannot read key bindings: {err}");
+        process::exit(1);
+    });
+    if !args.players.is_empty() || !args.bots.is_empty() || !args.bot_cmds.is_empty() {
+        let humans = if args.players.is_empty() {
+            vec![String::from("Player 1")]
+        } else {
+            args.players
+        };
+        let bots = args.bots.iter().map(|d| Player::bot(d.to_string(), *d));
+        let programs = args
+            .bot_cmds
+            .iter()
+            .map(|command| Player::external(external::program_name(command), command));
+        app.lineup = humans.into_iter().map(Player::new).chain(bots).chain(programs).collect();
+    }
+
+    if args.daily && args.replay.is_none() {
+        app.start_daily();
+    }
+    let network = if let Some(port) = args.host {
+        TcpListener::bind(("0.0.0.0", port)).and_then(|listener| {
+            eprintln!("Waiting for a player to join on port {port}...");
+            let (host, name) = Host::accept(listener, JOIN_TIMEOUT)?;
+            app.host_game(host, name)
+        })
+    } else if let Some(addr) = &args.join {
+        let name = app.lineup[0].name.clone();
+        Guest::join(addr.as_str(), &name).and_then(|(guest, game)| app.join_game(guest, game))
+    } else if let Some(addr) = &args.watch {
+        let name = app.lineup[0].name.clone();
+        Guest::watch(addr.as_str(), &name).and_then(|(guest, game)| app.join_game(guest, game))
+    } else {
+        Ok(())
+    };
+    if let Err(err) = network {
+        eprintln!("cannot start the network game: {err}");
+        process::exit(1);
+    }
+
     let mut terminal = ratatui::init();
-    let app_result = App::new().run(&mut terminal);
+    let app_result = app.run(&mut terminal);
     ratatui::restore();
+    if let Some(text) = app.daily_share_text() {
+        println!("{text}");
+    }
     app_result
 }
 
@@ -1 +1,5 @@
+pub mod die;
+pub mod house_rules;
+pub mod player;
 pub mod roll;
+pub mod ruleset;
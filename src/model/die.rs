@@ -0,0 +1,25 @@
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct DieFace {
+    pub value: u8,
+    pub held: bool,
+    #[serde(skip)]
+    pub rolling_until: Option<Instant>,
+}
+
+impl DieFace {
+    pub fn new(value: u8) -> Self {
+        Self {
+            value,
+            held: false,
+            rolling_until: None,
+        }
+    }
+
+    pub fn is_rolling(self) -> bool {
+        self.rolling_until.is_some()
+    }
+}
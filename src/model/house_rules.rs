@@ -0,0 +1,139 @@
+use std::{fmt, fs, io, path::{Path, PathBuf}, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::storage;
+
+/// Most rolls a turn may allow, so the dice can still be shown.
+pub const MAX_ROLLS_PER_TURN: u8 = 9;
+
+/// Changes to a ruleset's scoring that players agree on, read from
+/// `rules.toml`. Anything left out plays as the ruleset says.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HouseRules {
+    /// Upper section total needed for the bonus.
+    pub upper_bonus_threshold: Option<u32>,
+    /// Points for reaching the upper bonus threshold.
+    pub upper_bonus_points: Option<u32>,
+    /// Points for each Yahtzee after the first, none at 0.
+    pub yahtzee_bonus: Option<u32>,
+    /// Whether a Yahtzee also counts as a Full House.
+    pub full_house_yahtzee: bool,
+    /// Whether a Small Straight must be exactly four in a row, the fifth
+    /// die repeating one of them.
+    pub exact_small_straight: bool,
+    pub rolls_per_turn: Option<u8>,
+    /// Whether a bonus Yahtzee is a Joker.
+    pub jokers: Option<bool>,
+}
+
+impl HouseRules {
+    /// Reads the rules from `path`, or from the config directory if no
+    /// file is given. A missing `rules.toml` means no house rules.
+    pub fn load(path: Option<&Path>) -> io::Result<HouseRules> {
+        let contents = match path {
+            Some(path) => fs::read_to_string(path)?,
+            None => match rules_path().map(fs::read_to_string) {
+                Some(Ok(contents)) => contents,
+                Some(Err(err)) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+                _ => return Ok(HouseRules::default()),
+            },
+        };
+        contents
+            .parse()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == HouseRules::default()
+    }
+}
+
+impl FromStr for HouseRules {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<HouseRules, String> {
+        let rules: HouseRules = toml::from_str(s).map_err(|err| err.message().to_string())?;
+        if rules
+            .rolls_per_turn
+            .is_some_and(|rolls| !(1..=MAX_ROLLS_PER_TURN).contains(&rolls))
+        {
+            return Err(format!("rolls_per_turn must be 1 to {MAX_ROLLS_PER_TURN}"));
+        }
+        // a ruleset without an upper bonus, like Yacht, needs both to have one
+        if rules.upper_bonus_threshold.is_some() != rules.upper_bonus_points.is_some() {
+            return Err(String::from(
+                "upper_bonus_threshold and upper_bonus_points must be given together",
+            ));
+        }
+        Ok(rules)
+    }
+}
+
+/// Lists the rules that differ from the ruleset's, such as
+/// "bonus 35 at 70, 4 rolls, no Jokers".
+impl fmt::Display for HouseRules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rules = Vec::new();
+        match (self.upper_bonus_threshold, self.upper_bonus_points) {
+            (Some(threshold), Some(points)) => rules.push(format!("bonus {points} at {threshold}")),
+            (Some(threshold), None) => rules.push(format!("bonus at {threshold}")),
+            (None, Some(points)) => rules.push(format!("bonus {points}")),
+            (None, None) => {}
+        }
+        match self.yahtzee_bonus {
+            Some(0) => rules.push(String::from("no Yahtzee bonus")),
+            Some(bonus) => rules.push(format!("Yahtzee bonus {bonus}")),
+            None => {}
+        }
+        if self.full_house_yahtzee {
+            rules.push(String::from("Yahtzee is a Full House"));
+        }
+        if self.exact_small_straight {
+            rules.push(String::from("exact Small Straight"));
+        }
+        if let Some(rolls) = self.rolls_per_turn {
+            rules.push(format!("{rolls} rolls"));
+        }
+        match self.jokers {
+            Some(true) => rules.push(String::from("Jokers")),
+            Some(false) => rules.push(String::from("no Jokers")),
+            None => {}
+        }
+        if rules.is_empty() {
+            f.write_str("none")
+        } else {
+            f.write_str(&rules.join(", "))
+        }
+    }
+}
+
+fn rules_path() -> Option<PathBuf> {
+    storage::config_dir().map(|dir| dir.join("rules.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rules() {
+        let rules: HouseRules =
+            "upper_bonus_threshold = 70\nupper_bonus_points = 35\nrolls_per_turn = 4\njokers = false\n"
+                .parse()
+                .unwrap();
+        assert_eq!(rules.upper_bonus_threshold, Some(70));
+        assert_eq!(rules.upper_bonus_points, Some(35));
+        assert_eq!(rules.rolls_per_turn, Some(4));
+        assert_eq!(rules.jokers, Some(false));
+        assert!(!rules.full_house_yahtzee);
+        assert_eq!(rules.to_string(), "bonus 35 at 70, 4 rolls, no Jokers");
+
+        assert!("".parse::<HouseRules>().unwrap().is_default());
+        assert!("rolls_per_turn = 0".parse::<HouseRules>().is_err());
+        assert!("jokerz = true".parse::<HouseRules>().is_err());
+        assert!("upper_bonus_threshold = 70".parse::<HouseRules>().is_err());
+        assert!("upper_bonus_points = 50".parse::<HouseRules>().is_err());
+    }
+}
@@ -0,0 +1,233 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bot::Difficulty,
+    model::roll::{AllRolls, move_selection},
+};
+
+pub const MAX_PLAYERS: usize = 6;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Player {
+    pub name: String,
+    /// Scorecard columns, the first counting once, the second twice and so on.
+    pub columns: Vec<AllRolls>,
+    /// Set for computer players.
+    #[serde(default)]
+    pub bot: Option<Difficulty>,
+    /// Rolls left unused in earlier turns, under rules that save them.
+    #[serde(default)]
+    pub saved_rolls: u8,
+    /// Shell command of an external program playing for the player, see
+    /// `bot::external`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Set once the player is out of the game, ranking below everyone.
+    #[serde(default)]
+    pub forfeited: bool,
+}
+
+impl Player {
+    pub fn new(name: impl Into<String>) -> Player {
+        Player {
+            name: name.into(),
+            columns: vec![AllRolls::new()],
+            bot: None,
+            saved_rolls: 0,
+            command: None,
+            forfeited: false,
+        }
+    }
+
+    pub fn bot(name: impl Into<String>, difficulty: Difficulty) -> Player {
+        Player {
+            bot: Some(difficulty),
+            ..Player::new(name)
+        }
+    }
+
+    /// A player whose moves come from the program `command` runs.
+    pub fn external(name: impl Into<String>, command: impl Into<String>) -> Player {
+        Player {
+            command: Some(command.into()),
+            ..Player::new(name)
+        }
+    }
+
+    /// Gives the player `count` scorecard columns.
+    pub fn with_columns(mut self, count: usize) -> Player {
+        self.columns.resize(count.max(1), AllRolls::new());
+        self
+    }
+
+    pub fn is_bot(&self) -> bool {
+        self.bot.is_some() || self.command.is_some()
+    }
+
+    /// The first column, which is the whole scorecard outside Triple games.
+    pub fn rolls(&self) -> &AllRolls {
+        &self.columns[0]
+    }
+
+    pub fn rolls_mut(&mut self) -> &mut AllRolls {
+        &mut self.columns[0]
+    }
+
+    /// What column `column` counts for.
+    pub fn weight(column: usize) -> u32 {
+        column as u32 + 1
+    }
+
+    /// Every column's total, bonuses included, times its weight.
+    pub fn total_score(&self) -> u32 {
+        self.columns
+            .iter()
+            .enumerate()
+            .map(|(i, rolls)| Player::weight(i) * rolls.total_score())
+            .sum()
+    }
+
+    /// Upper section bonuses earned, weighted like the columns.
+    pub fn upper_bonus(&self) -> u32 {
+        self.columns
+            .iter()
+            .enumerate()
+            .map(|(i, rolls)| Player::weight(i) * rolls.bonus_status().1)
+            .sum()
+    }
+
+    pub fn yahtzee_bonus_count(&self) -> u32 {
+        self.columns.iter().map(AllRolls::yahtzee_bonus_count).sum()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.columns.iter().all(AllRolls::is_complete)
+    }
+
+    /// The column holding the selection, or else the first one with open slots.
+    pub fn active_column(&self) -> usize {
+        self.columns
+            .iter()
+            .position(|rolls| rolls.iter().any(|r| r.selected))
+            .or_else(|| self.columns.iter().position(|rolls| !rolls.is_complete()))
+            .unwrap_or(0)
+    }
+
+    pub fn clear_selection(&mut self) {
+        for rolls in &mut self.columns {
+            rolls.clear_selection();
+        }
+    }
+
+    /// Moves the selection to the next open slot, on into the next column.
+    pub fn select_next(&mut self) {
+        move_selection(self.columns.iter_mut().flat_map(AllRolls::iter_mut).collect(), true);
+    }
+
+    pub fn select_prev(&mut self) {
+        move_selection(self.columns.iter_mut().flat_map(AllRolls::iter_mut).collect(), false);
+    }
+
+    /// Moves the selection to the next or previous column with open slots,
+    /// keeping the category if it is open there.
+    pub fn select_column(&mut self, forward: bool) {
+        let len = self.columns.len();
+        let current = self.active_column();
+        let Some(roll_type) = self.columns[current].iter().find(|r| r.selected).map(|r| r.roll_type) else {
+            return;
+        };
+        let next = (1..len)
+            .map(|i| if forward { (current + i) % len } else { (current + len - i) % len })
+            .find(|i| !self.columns[*i].is_complete());
+        if let Some(next) = next {
+            self.clear_selection();
+            let rolls = &mut self.columns[next];
+            if rolls.roll(roll_type).score.is_none() {
+                rolls.roll_mut(roll_type).selected = true;
+            } else {
+                rolls.select_next();
+            }
+        }
+    }
+}
+
+/// Ranks players by total score, best first. Tied players share a rank and
+/// the next rank is skipped, so two players tied for first are followed by third.
+/// Players who forfeited come last, whatever they scored.
+pub fn standings(players: &[Player]) -> Vec<(usize, &Player)> {
+    let key = |p: &Player| (p.forfeited, std::cmp::Reverse(p.total_score()));
+    let mut sorted: Vec<&Player> = players.iter().collect();
+    sorted.sort_by_key(|p| key(p));
+
+    let mut ranked: Vec<(usize, &Player)> = Vec::with_capacity(sorted.len());
+    for (i, player) in sorted.into_iter().enumerate() {
+        let rank = match ranked.last() {
+            Some((rank, prev)) if key(prev) == key(player) => *rank,
+            _ => i + 1,
+        };
+        ranked.push((rank, player));
+    }
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::roll::RollType;
+
+    fn player(name: &str, chance: u32) -> Player {
+        let mut player = Player::new(name);
+        player.rolls_mut().roll_mut(RollType::Chance).score = Some(chance);
+        player
+    }
+
+    #[test]
+    fn test_columns_are_weighted() {
+        let mut player = Player::new("a").with_columns(3);
+        for (column, score) in [(0, 20), (1, 10), (2, 5)] {
+            player.columns[column].roll_mut(RollType::Chance).score = Some(score);
+        }
+        player.columns[2].roll_mut(RollType::Sixes).score = Some(30);
+        player.columns[2].roll_mut(RollType::Fives).score = Some(25);
+        player.columns[2].roll_mut(RollType::Fours).score = Some(16);
+        assert_eq!(player.upper_bonus(), 3 * 35);
+        assert_eq!(player.total_score(), 20 + 2 * 10 + 3 * (5 + 71 + 35));
+    }
+
+    #[test]
+    fn test_selection_moves_across_columns() {
+        let mut player = Player::new("a").with_columns(3);
+        player.columns[0].roll_mut(RollType::Yahtzee).score = Some(0);
+        player.columns[0].roll_mut(RollType::Chance).selected = true;
+        player.select_next();
+        assert_eq!(player.active_column(), 1);
+        assert!(player.columns[1].roll(RollType::Ones).selected);
+        player.select_prev();
+        assert!(player.columns[0].roll(RollType::Chance).selected);
+
+        player.columns[1].roll_mut(RollType::Chance).score = Some(20);
+        player.select_column(false);
+        assert!(player.columns[2].roll(RollType::Chance).selected);
+        player.select_column(false);
+        assert_eq!(player.active_column(), 1);
+        assert!(player.columns[1].roll(RollType::Ones).selected);
+    }
+
+    #[test]
+    fn test_standings_with_ties() {
+        let players = vec![player("a", 10), player("b", 30), player("c", 20), player("d", 30)];
+        let ranks: Vec<(usize, &str)> = standings(&players)
+            .into_iter()
+            .map(|(rank, p)| (rank, p.name.as_str()))
+            .collect();
+        assert_eq!(ranks, vec![(1, "b"), (1, "d"), (3, "c"), (4, "a")]);
+
+        let mut players = players;
+        players[1].forfeited = true;
+        let ranks: Vec<(usize, &str)> = standings(&players)
+            .into_iter()
+            .map(|(rank, p)| (rank, p.name.as_str()))
+            .collect();
+        assert_eq!(ranks, vec![(1, "d"), (2, "c"), (3, "a"), (4, "b")]);
+    }
+}
@@ -1,4 +1,8 @@
-#[derive(Clone, Copy, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+use crate::model::{house_rules::HouseRules, ruleset::{Ruleset, UpperBonus}};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum RollType {
     Ones,
     Twos,
@@ -6,16 +10,56 @@ pub enum RollType {
     Fours,
     Fives,
     Sixes,
+    OnePair,
+    TwoPairs,
+    ThreePairs,
     ThreeOfAKind,
     FourOfAKind,
     FullHouse,
+    /// Two sets of three.
+    Castle,
+    /// Four of a kind and a pair.
+    Tower,
     SmallStraight,
     LargeStraight,
+    /// One to six.
+    FullStraight,
     Chance,
     Yahtzee,
 }
 
-#[derive(Clone, Copy)]
+impl RollType {
+    /// The standard scorecard's categories, in order.
+    pub const STANDARD: [RollType; 13] = [
+        RollType::Ones,
+        RollType::Twos,
+        RollType::Threes,
+        RollType::Fours,
+        RollType::Fives,
+        RollType::Sixes,
+        RollType::ThreeOfAKind,
+        RollType::FourOfAKind,
+        RollType::FullHouse,
+        RollType::SmallStraight,
+        RollType::LargeStraight,
+        RollType::Chance,
+        RollType::Yahtzee,
+    ];
+
+    pub fn is_upper(self) -> bool {
+        matches!(
+            self,
+            RollType::Ones
+                | RollType::Twos
+                | RollType::Threes
+                | RollType::Fours
+                | RollType::Fives
+                | RollType::Sixes
+        )
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub struct Roll {
     pub roll_type: RollType,
     pub score: Option<u32>,
@@ -32,89 +76,134 @@ impl Roll {
     }
 }
 
-#[derive(Clone, Copy)]
+/// A scorecard column: a slot for each category of its ruleset.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct AllRolls {
-    pub ones_roll: Roll,
-    pub twos_roll: Roll,
-    pub threes_roll: Roll,
-    pub fours_roll: Roll,
-    pub fives_roll: Roll,
-    pub sixes_roll: Roll,
-    pub three_of_a_kind_roll: Roll,
-    pub four_of_a_kind_roll: Roll,
-    pub full_house_roll: Roll,
-    pub small_straight_roll: Roll,
-    pub large_straight_roll: Roll,
-    pub chance_roll: Roll,
-    pub yahtzee_roll: Roll,
+    pub ruleset: Ruleset,
+    #[serde(default)]
+    pub house_rules: HouseRules,
+    rolls: Vec<Roll>,
+}
+
+impl Default for AllRolls {
+    fn default() -> Self {
+        AllRolls::new()
+    }
 }
 
 impl AllRolls {
+    /// An empty scorecard under the standard rules.
     pub fn new() -> AllRolls {
+        AllRolls::with_ruleset(Ruleset::Standard)
+    }
+
+    pub fn with_ruleset(ruleset: Ruleset) -> AllRolls {
+        AllRolls::with_rules(ruleset, HouseRules::default())
+    }
+
+    pub fn with_rules(ruleset: Ruleset, house_rules: HouseRules) -> AllRolls {
         AllRolls {
-            ones_roll: Roll::new(RollType::Ones),
-            twos_roll: Roll::new(RollType::Twos),
-            threes_roll: Roll::new(RollType::Threes),
-            fours_roll: Roll::new(RollType::Fours),
-            fives_roll: Roll::new(RollType::Fives),
-            sixes_roll: Roll::new(RollType::Sixes),
-            three_of_a_kind_roll: Roll::new(RollType::ThreeOfAKind),
-            four_of_a_kind_roll: Roll::new(RollType::FourOfAKind),
-            full_house_roll: Roll::new(RollType::FullHouse),
-            small_straight_roll: Roll::new(RollType::SmallStraight),
-            large_straight_roll: Roll::new(RollType::LargeStraight),
-            chance_roll: Roll::new(RollType::Chance),
-            yahtzee_roll: Roll::new(RollType::Yahtzee),
+            ruleset,
+            house_rules,
+            rolls: ruleset.categories().iter().map(|r| Roll::new(*r)).collect(),
         }
     }
 
-    pub fn bonus_status(self) -> (u32, u32) {
-        let progress = self.ones_roll.score.unwrap_or(0)
-            + self.twos_roll.score.unwrap_or(0)
-            + self.threes_roll.score.unwrap_or(0)
-            + self.fours_roll.score.unwrap_or(0)
-            + self.fives_roll.score.unwrap_or(0)
-            + self.sixes_roll.score.unwrap_or(0);
+    /// The ruleset's upper bonus as changed by the house rules, which may
+    /// also add one to a ruleset without.
+    pub fn upper_bonus(&self) -> Option<UpperBonus> {
+        let house = self.house_rules;
+        match (self.ruleset.upper_bonus(), house.upper_bonus_threshold, house.upper_bonus_points) {
+            (Some(bonus), threshold, points) => Some(UpperBonus {
+                threshold: threshold.unwrap_or(bonus.threshold),
+                points: points.unwrap_or(bonus.points),
+            }),
+            (None, Some(threshold), Some(points)) => Some(UpperBonus { threshold, points }),
+            (None, _, _) => None,
+        }
+    }
+
+    /// Points for each Yahtzee after the first, if they score any.
+    pub fn yahtzee_bonus(&self) -> Option<u32> {
+        self.house_rules
+            .yahtzee_bonus
+            .or(self.ruleset.yahtzee_bonus())
+            .filter(|bonus| *bonus > 0)
+    }
 
-        (progress, if progress >= 63 { 35 } else { 0 })
+    /// Whether a bonus Yahtzee is a Joker, which by default only those
+    /// rulesets with a Yahtzee bonus have.
+    pub fn jokers(&self) -> bool {
+        self.house_rules.jokers.unwrap_or(self.ruleset.yahtzee_bonus().is_some())
+    }
+
+    /// Whether the strategy table covers how this scorecard is scored.
+    pub fn has_strategy_table(&self) -> bool {
+        self.ruleset.has_strategy_table() && self.house_rules.is_default()
+    }
+
+    /// The upper section total, and the bonus it has earned.
+    pub fn bonus_status(&self) -> (u32, u32) {
+        let progress = self
+            .iter()
+            .filter(|r| r.roll_type.is_upper())
+            .map(|r| r.score.unwrap_or(0))
+            .sum();
+
+        match self.upper_bonus() {
+            Some(bonus) if progress >= bonus.threshold => (progress, bonus.points),
+            _ => (progress, 0),
+        }
+    }
+
+    pub fn total_score(&self) -> u32 {
+        let total = self
+            .iter()
+            .fold(0, |tot, r| tot + r.score.unwrap_or(0));
+        let (_, bonus) = self.bonus_status();
+        total + bonus
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.iter().all(|r| r.score.is_some())
+    }
+
+    /// Number of bonus Yahtzees scored after the first one.
+    pub fn yahtzee_bonus_count(&self) -> u32 {
+        let Some(bonus) = self.yahtzee_bonus() else {
+            return 0;
+        };
+        let points = self.ruleset.yahtzee_points();
+        match self.roll(RollType::Yahtzee).score {
+            Some(s) if s >= points => (s - points) / bonus,
+            _ => 0,
+        }
+    }
+
+    /// The upper section slot that counts dice showing `value`.
+    pub fn upper_roll_for(&self, value: u8) -> &Roll {
+        self.roll(RollType::STANDARD[value.clamp(1, 6) as usize - 1])
+    }
+
+    pub fn has(&self, roll_type: RollType) -> bool {
+        self.rolls.iter().any(|r| r.roll_type == roll_type)
     }
 
     pub fn iter(&self) -> impl Iterator<Item = &Roll> {
-        [
-            &self.ones_roll,
-            &self.twos_roll,
-            &self.threes_roll,
-            &self.fours_roll,
-            &self.fives_roll,
-            &self.sixes_roll,
-            &self.three_of_a_kind_roll,
-            &self.four_of_a_kind_roll,
-            &self.full_house_roll,
-            &self.small_straight_roll,
-            &self.large_straight_roll,
-            &self.chance_roll,
-            &self.yahtzee_roll,
-        ]
-        .into_iter()
-    }
-
-    fn iter_mut(&mut self) -> impl Iterator<Item = &mut Roll> {
-        [
-            &mut self.ones_roll,
-            &mut self.twos_roll,
-            &mut self.threes_roll,
-            &mut self.fours_roll,
-            &mut self.fives_roll,
-            &mut self.sixes_roll,
-            &mut self.three_of_a_kind_roll,
-            &mut self.four_of_a_kind_roll,
-            &mut self.full_house_roll,
-            &mut self.small_straight_roll,
-            &mut self.large_straight_roll,
-            &mut self.chance_roll,
-            &mut self.yahtzee_roll,
-        ]
-        .into_iter()
+        self.rolls.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Roll> {
+        self.rolls.iter_mut()
+    }
+
+    /// The slot for `roll_type`, which must be on this scorecard.
+    pub fn roll(&self, roll_type: RollType) -> &Roll {
+        self.iter().find(|r| r.roll_type == roll_type).unwrap()
+    }
+
+    pub fn roll_mut(&mut self, roll_type: RollType) -> &mut Roll {
+        self.iter_mut().find(|r| r.roll_type == roll_type).unwrap()
     }
 
     pub fn selected(&mut self) -> Option<&mut Roll> {
@@ -128,54 +217,34 @@ impl AllRolls {
     }
 
     pub fn select_next(&mut self) {
-        let mut rolls: Vec<&mut Roll> = self.iter_mut().collect();
-        
-        // Find current selection index
-        let current_idx = rolls.iter().position(|r| r.selected);
-        
-        // Start from next position, or 0 if nothing selected
-        let start_idx = current_idx.map_or(0, |i| (i + 1) % rolls.len());
-        
-        for i in 0..rolls.len() {
-            rolls[i].selected = false;
-        }
-
-        for i in 0..rolls.len() {
-            let idx = (start_idx + i) % rolls.len();
-            if rolls[idx].score.is_none() {
-                rolls[idx].selected = true;
-                break;
-            }
-        }
+        move_selection(self.iter_mut().collect(), true);
     }
 
     pub fn select_prev(&mut self) {
-        let mut rolls: Vec<&mut Roll> = self.iter_mut().collect();
-        
-        // Find current selection index
-        let current_idx = rolls.iter().position(|r| r.selected);
-        
-        // Start from prev position, or 0 if nothing selected
-        let start_idx = match current_idx {
-            Some(0) => rolls.len() - 1,
-            Some(i) => i - 1,
-            None => 0
-        };
-        
-        for i in 0..rolls.len() {
-            rolls[i].selected = false;
-        }
+        move_selection(self.iter_mut().collect(), false);
+    }
+}
+
+/// Moves the selection in `rolls` to the next or previous open slot,
+/// wrapping around, or to the first open slot if nothing is selected.
+pub fn move_selection(mut rolls: Vec<&mut Roll>, forward: bool) {
+    let len = rolls.len();
+    let current = rolls.iter().position(|r| r.selected);
+    let start = match current {
+        Some(i) if forward => (i + 1) % len,
+        Some(i) => (i + len - 1) % len,
+        None => 0,
+    };
+
+    for roll in rolls.iter_mut() {
+        roll.selected = false;
+    }
 
-        for i in 0..rolls.len() {
-            let idx = if i <= start_idx {
-                start_idx - i
-            } else {
-                rolls.len() - (i - start_idx)
-            };
-            if rolls[idx].score.is_none() {
-                rolls[idx].selected = true;
-                break;
-            }
+    for i in 0..len {
+        let idx = if forward { (start + i) % len } else { (start + len - i) % len };
+        if rolls[idx].score.is_none() {
+            rolls[idx].selected = true;
+            break;
         }
     }
 }
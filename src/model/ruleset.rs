@@ -0,0 +1,167 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::roll::RollType;
+
+/// Scoring rules: which categories a scorecard has, what they score and
+/// which bonuses apply.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ruleset {
+    /// Today's Yahtzee, with the upper bonus, bonus Yahtzees and Jokers.
+    #[default]
+    Standard,
+    /// The original Yacht: no Three of a Kind, no bonuses, a Four of a Kind
+    /// counting only the four dice and fixed 1-5 and 2-6 straights worth 30.
+    Yacht,
+    /// Maxi Yahtzee: six dice, pairs, Castle, Tower and a Full Straight, and
+    /// rolls left unused are saved for later turns.
+    Maxi,
+}
+
+/// Points for reaching a total in the upper section.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UpperBonus {
+    pub threshold: u32,
+    pub points: u32,
+}
+
+impl Ruleset {
+    pub const ALL: [Ruleset; 3] = [Ruleset::Standard, Ruleset::Yacht, Ruleset::Maxi];
+
+    /// The scorecard's categories, in order.
+    pub fn categories(self) -> &'static [RollType] {
+        match self {
+            Ruleset::Standard => &RollType::STANDARD,
+            Ruleset::Yacht => &[
+                RollType::Ones,
+                RollType::Twos,
+                RollType::Threes,
+                RollType::Fours,
+                RollType::Fives,
+                RollType::Sixes,
+                RollType::FourOfAKind,
+                RollType::FullHouse,
+                RollType::SmallStraight,
+                RollType::LargeStraight,
+                RollType::Chance,
+                RollType::Yahtzee,
+            ],
+            Ruleset::Maxi => &[
+                RollType::Ones,
+                RollType::Twos,
+                RollType::Threes,
+                RollType::Fours,
+                RollType::Fives,
+                RollType::Sixes,
+                RollType::OnePair,
+                RollType::TwoPairs,
+                RollType::ThreePairs,
+                RollType::ThreeOfAKind,
+                RollType::FourOfAKind,
+                RollType::SmallStraight,
+                RollType::LargeStraight,
+                RollType::FullStraight,
+                RollType::FullHouse,
+                RollType::Castle,
+                RollType::Tower,
+                RollType::Chance,
+                RollType::Yahtzee,
+            ],
+        }
+    }
+
+    pub const fn dice_count(self) -> usize {
+        match self {
+            Ruleset::Standard | Ruleset::Yacht => 5,
+            Ruleset::Maxi => 6,
+        }
+    }
+
+    /// Whether rolls a player leaves unused carry over to their later turns.
+    pub fn saves_rolls(self) -> bool {
+        self == Ruleset::Maxi
+    }
+
+    pub fn upper_bonus(self) -> Option<UpperBonus> {
+        match self {
+            Ruleset::Standard => Some(UpperBonus {
+                threshold: 63,
+                points: 35,
+            }),
+            Ruleset::Maxi => Some(UpperBonus {
+                threshold: 84,
+                points: 50,
+            }),
+            Ruleset::Yacht => None,
+        }
+    }
+
+    /// Points for the first Yahtzee.
+    pub fn yahtzee_points(self) -> u32 {
+        match self {
+            Ruleset::Standard | Ruleset::Yacht => 50,
+            Ruleset::Maxi => 100,
+        }
+    }
+
+    /// Points for each Yahtzee after the first, which also makes it a Joker.
+    pub fn yahtzee_bonus(self) -> Option<u32> {
+        match self {
+            Ruleset::Standard => Some(100),
+            Ruleset::Yacht | Ruleset::Maxi => None,
+        }
+    }
+
+    /// What `roll_type` scores when made, for categories worth a fixed
+    /// amount rather than the dice showing. A Joker scores this too.
+    pub fn fixed_points(self, roll_type: RollType) -> Option<u32> {
+        match (self, roll_type) {
+            (Ruleset::Standard, RollType::FullHouse) => Some(25),
+            (Ruleset::Standard, RollType::SmallStraight) => Some(30),
+            (Ruleset::Standard, RollType::LargeStraight) => Some(40),
+            (Ruleset::Yacht, RollType::SmallStraight | RollType::LargeStraight) => Some(30),
+            (Ruleset::Maxi, RollType::SmallStraight) => Some(15),
+            (Ruleset::Maxi, RollType::LargeStraight) => Some(20),
+            (Ruleset::Maxi, RollType::FullStraight) => Some(21),
+            _ => None,
+        }
+    }
+
+    /// Whether the strategy table, and so hints and the optimal bot, cover these rules.
+    pub fn has_strategy_table(self) -> bool {
+        self == Ruleset::Standard
+    }
+
+    /// The name of the game played by these rules.
+    pub fn game_name(self) -> &'static str {
+        match self {
+            Ruleset::Standard => "Yahtzee",
+            Ruleset::Yacht => "Yacht",
+            Ruleset::Maxi => "Maxi Yahtzee",
+        }
+    }
+}
+
+impl fmt::Display for Ruleset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Ruleset::Standard => "Standard",
+            Ruleset::Yacht => "Yacht",
+            Ruleset::Maxi => "Maxi",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Ruleset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Ruleset, String> {
+        Ruleset::ALL
+            .into_iter()
+            .find(|r| r.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown ruleset: {s}"))
+    }
+}
@@ -0,0 +1,616 @@
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs},
+    sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::{
+    game::{Event, Game, roller::DiceRoller},
+    model::roll::RollType,
+    replay::Replay,
+};
+
+/// How long either side waits for the other to introduce itself.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long a host waits for a player to join.
+pub const JOIN_TIMEOUT: Duration = Duration::from_secs(600);
+/// How long a send may block on a peer that stops reading before the
+/// connection is given up.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+/// How often a waiting host checks for a connection.
+const ACCEPT_POLL: Duration = Duration::from_millis(50);
+/// The longest line either side reads, enough for the welcome of a long
+/// game. A peer sending more is treated as having hung up.
+const MAX_LINE_LEN: usize = 1 << 20;
+
+/// What a client sends the host, one JSON object per line.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Command {
+    /// The first message, taking a seat under `name`. Once the game has
+    /// started it only gets a spectator's place.
+    Join { name: String },
+    /// The first message of a spectator.
+    Watch { name: String },
+    Roll,
+    Hold { die: usize },
+    Score { column: usize, category: RollType },
+}
+
+/// What the host sends its clients, one JSON object per line.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum HostMessage {
+    /// The answer to `Join` or `Watch`: the seat taken, none for
+    /// spectators, and the game so far.
+    Welcome { seat: Option<usize>, game: Replay },
+    /// A step of the game, which clients play on their copy of it.
+    Event { event: Event },
+    /// Why the client's last command was refused.
+    Rejected { reason: String },
+}
+
+/// A TCP connection carrying a JSON message per line, read on its own
+/// thread so the game never waits on the network.
+pub struct Connection<In> {
+    stream: TcpStream,
+    incoming: Receiver<In>,
+    closed: bool,
+}
+
+impl<In: DeserializeOwned + Send + 'static> Connection<In> {
+    pub fn new(stream: TcpStream) -> io::Result<Connection<In>> {
+        stream.set_nodelay(true)?;
+        stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let (sender, incoming) = mpsc::channel();
+        thread::spawn(move || {
+            // a line that is too long or does not parse ends the connection like a hang-up
+            while let Some(message) = read_line(&mut reader).and_then(|line| serde_json::from_slice(&line).ok()) {
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Connection {
+            stream,
+            incoming,
+            closed: false,
+        })
+    }
+
+    /// Sends `message`, closing the connection if that fails or times out,
+    /// since a partly written line leaves the peer out of step.
+    pub fn send(&mut self, message: &impl Serialize) -> io::Result<()> {
+        if self.closed {
+            return Err(io::Error::new(io::ErrorKind::NotConnected, "the connection closed"));
+        }
+        let mut line = serde_json::to_vec(message).map_err(io::Error::other)?;
+        line.push(b'\n');
+        let result = self.stream.write_all(&line);
+        if result.is_err() {
+            self.closed = true;
+        }
+        result
+    }
+
+    /// The next message if one has arrived.
+    pub fn try_recv(&mut self) -> Option<In> {
+        match self.incoming.try_recv() {
+            Ok(message) => Some(message),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                self.closed = true;
+                None
+            }
+        }
+    }
+
+    /// Waits for the next message, failing if none comes in time.
+    pub fn recv_timeout(&mut self, timeout: Duration) -> io::Result<In> {
+        self.incoming.recv_timeout(timeout).map_err(|err| match err {
+            RecvTimeoutError::Timeout => io::Error::new(io::ErrorKind::TimedOut, "no answer in time"),
+            RecvTimeoutError::Disconnected => {
+                self.closed = true;
+                io::Error::new(io::ErrorKind::ConnectionAborted, "the connection closed")
+            }
+        })
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+}
+
+/// The next line from `reader`, or None at the end of the stream or once a
+/// line runs past `MAX_LINE_LEN`.
+fn read_line(reader: &mut impl BufRead) -> Option<Vec<u8>> {
+    let mut line = Vec::new();
+    let read = reader.by_ref().take(MAX_LINE_LEN as u64).read_until(b'\n', &mut line).ok()?;
+    if read == 0 || (read == MAX_LINE_LEN && line.last() != Some(&b'\n')) {
+        return None;
+    }
+    Some(line)
+}
+
+impl<In> Drop for Connection<In> {
+    fn drop(&mut self) {
+        // the reader thread holds a clone of the stream, which would keep it open
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+}
+
+/// A client of the host, playing at `seat` or watching without one.
+struct Remote {
+    connection: Connection<Command>,
+    seat: Option<usize>,
+}
+
+/// The authoritative end of a network game: it rolls the dice, checks and
+/// scores every move and sends each step on to the clients.
+pub struct Host {
+    listener: TcpListener,
+    remotes: Vec<Remote>,
+    /// Connections that have not said who they are yet, with when they
+    /// have to by.
+    pending: Vec<(Instant, Connection<Command>)>,
+    /// Spectators let in before the game started, not yet announced.
+    early: Vec<String>,
+    /// Events of the game already sent to the clients.
+    sent: usize,
+}
+
+impl Host {
+    /// Waits on `listener` for up to `timeout` for a player to join,
+    /// returning its name. Whoever comes to watch in the meantime, or
+    /// connects after that, watches.
+    pub fn accept(listener: TcpListener, timeout: Duration) -> io::Result<(Host, String)> {
+        listener.set_nonblocking(true)?;
+        let deadline = Instant::now() + timeout;
+        let mut host = Host {
+            listener,
+            remotes: Vec::new(),
+            pending: Vec::new(),
+            early: Vec::new(),
+            sent: 0,
+        };
+        loop {
+            host.listen()?;
+            let mut waiting = std::mem::take(&mut host.pending).into_iter();
+            while let Some((by, mut connection)) = waiting.next() {
+                match connection.try_recv() {
+                    Some(Command::Join { name }) => {
+                        host.remotes.push(Remote {
+                            connection,
+                            seat: Some(1),
+                        });
+                        host.pending.extend(waiting);
+                        return Ok((host, name));
+                    }
+                    Some(Command::Watch { name }) => {
+                        host.early.push(name);
+                        host.remotes.push(Remote { connection, seat: None });
+                    }
+                    // anything else is not a client of ours
+                    Some(_) => {}
+                    None if connection.is_closed() || Instant::now() >= by => {}
+                    None => host.pending.push((by, connection)),
+                }
+            }
+            if Instant::now() >= deadline {
+                return Err(io::Error::new(io::ErrorKind::TimedOut, "nobody joined in time"));
+            }
+            thread::sleep(ACCEPT_POLL);
+        }
+    }
+
+    /// Takes whoever connected since the last call as pending, failing
+    /// only if the listener itself does.
+    fn listen(&mut self) -> io::Result<()> {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, _)) => {
+                    // the stream may take on the listener's non-blocking mode
+                    if let Ok(connection) = stream.set_nonblocking(false).and_then(|()| Connection::new(stream)) {
+                        self.pending.push((Instant::now() + HANDSHAKE_TIMEOUT, connection));
+                    }
+                }
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Sends the clients their seats and the game as it stands.
+    pub fn welcome(&mut self, game: &Game) -> io::Result<()> {
+        let replay = Replay::from_game(game, None)
+            .ok_or_else(|| io::Error::other("the game was not recorded"))?;
+        self.sent = replay.len();
+        for remote in &mut self.remotes {
+            let welcome = HostMessage::Welcome {
+                seat: remote.seat,
+                game: replay.clone(),
+            };
+            let sent = remote.connection.send(&welcome);
+            // a spectator who is gone already is dropped with those who left
+            if remote.seat.is_some() {
+                sent?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Commands the players have sent, with the seat they came from.
+    /// Spectators are told they cannot play.
+    pub fn commands(&mut self) -> Vec<(usize, Command)> {
+        let mut commands = Vec::new();
+        for remote in &mut self.remotes {
+            while let Some(command) = remote.connection.try_recv() {
+                match remote.seat {
+                    Some(seat) => commands.push((seat, command)),
+                    None => {
+                        let reason = String::from("spectators cannot play");
+                        // a failed send shows up as a closed connection
+                        let _ = remote.connection.send(&HostMessage::Rejected { reason });
+                    }
+                }
+            }
+        }
+        commands
+    }
+
+    /// Tells the client at `seat` why its command was refused.
+    pub fn reject(&mut self, seat: usize, reason: String) {
+        for remote in self.remotes.iter_mut().filter(|r| r.seat == Some(seat)) {
+            let _ = remote.connection.send(&HostMessage::Rejected { reason: reason.clone() });
+        }
+    }
+
+    /// Sends the clients whatever happened in `game` since the last call.
+    pub fn broadcast(&mut self, game: &Game) {
+        let events = game.events().unwrap_or_default();
+        for event in &events[self.sent.min(events.len())..] {
+            let message = HostMessage::Event { event: event.clone() };
+            for remote in &mut self.remotes {
+                let _ = remote.connection.send(&message);
+            }
+        }
+        self.sent = events.len();
+    }
+
+    /// Lets in whoever connected since the last call as spectators, sending
+    /// them the game as it stands, and returns their names. The game should
+    /// have been broadcast first, so they go on from the same step.
+    pub fn admit_spectators(&mut self, game: &Game) -> Vec<String> {
+        // a failing listener only stops more spectators coming
+        let _ = self.listen();
+        let mut names = std::mem::take(&mut self.early);
+        for (deadline, mut connection) in std::mem::take(&mut self.pending) {
+            match connection.try_recv() {
+                Some(Command::Join { name } | Command::Watch { name }) => {
+                    let Some(replay) = Replay::from_game(game, None) else {
+                        continue;
+                    };
+                    let welcome = HostMessage::Welcome { seat: None, game: replay };
+                    if connection.send(&welcome).is_ok() {
+                        names.push(name);
+                        self.remotes.push(Remote { connection, seat: None });
+                    }
+                }
+                // anything else is not a client of ours
+                Some(_) => {}
+                None if connection.is_closed() || Instant::now() >= deadline => {}
+                None => self.pending.push((deadline, connection)),
+            }
+        }
+        names
+    }
+
+    /// Seats whose players have gone, dropping them and any spectators
+    /// who left.
+    pub fn departed(&mut self) -> Vec<usize> {
+        let (gone, kept): (Vec<Remote>, _) = self.remotes.drain(..).partition(|r| r.connection.is_closed());
+        self.remotes = kept;
+        gone.into_iter().filter_map(|r| r.seat).collect()
+    }
+}
+
+/// Plays a client's command on the host's game.
+pub fn apply_command(game: &mut Game, seat: usize, command: &Command, roller: &mut dyn DiceRoller) -> Result<(), String> {
+    if game.current_player_index() != seat {
+        return Err(String::from("it is not your turn"));
+    }
+    let result = match command {
+        Command::Join { .. } | Command::Watch { .. } => return Err(String::from("already joined")),
+        Command::Roll => game.roll(roller),
+        Command::Hold { die } => game.hold(*die),
+        Command::Score { column, category } => game.score_in(*column, *category).map(|_| ()),
+    };
+    result.map_err(|err| err.to_string())
+}
+
+/// The client end of a network game, playing at `seat` on a copy of the
+/// host's game, or watching it without a seat.
+pub struct Guest {
+    connection: Connection<HostMessage>,
+    pub seat: Option<usize>,
+    /// Whether the host hanging up has been noticed.
+    hung_up: bool,
+}
+
+impl Guest {
+    /// Joins the game hosted at `addr` as `name`, returning the game so far.
+    pub fn join(addr: impl ToSocketAddrs, name: &str) -> io::Result<(Guest, Replay)> {
+        Guest::connect(addr, Command::Join { name: name.to_string() })
+    }
+
+    /// Watches the game hosted at `addr`, returning the game so far.
+    pub fn watch(addr: impl ToSocketAddrs, name: &str) -> io::Result<(Guest, Replay)> {
+        Guest::connect(addr, Command::Watch { name: name.to_string() })
+    }
+
+    fn connect(addr: impl ToSocketAddrs, hello: Command) -> io::Result<(Guest, Replay)> {
+        let stream = TcpStream::connect(addr)?;
+        let mut connection = Connection::new(stream)?;
+        connection.send(&hello)?;
+        let HostMessage::Welcome { seat, game } = connection.recv_timeout(HANDSHAKE_TIMEOUT)? else {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "expected a welcome"));
+        };
+        let guest = Guest {
+            connection,
+            seat,
+            hung_up: false,
+        };
+        Ok((guest, game))
+    }
+
+    pub fn send(&mut self, command: &Command) {
+        // a failed send shows up as a closed connection
+        let _ = self.connection.send(command);
+    }
+
+    pub fn messages(&mut self) -> Vec<HostMessage> {
+        let mut messages = Vec::new();
+        while let Some(message) = self.connection.try_recv() {
+            messages.push(message);
+        }
+        messages
+    }
+
+    /// Whether the host has hung up since the last call; true only once.
+    pub fn host_hung_up(&mut self) -> bool {
+        if self.connection.is_closed() && !self.hung_up {
+            self.hung_up = true;
+            return true;
+        }
+        false
+    }
+}
+
+/// Either end of a network game.
+pub enum Session {
+    Host(Host),
+    Guest(Guest),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        game::roller::ScriptedRoller,
+        model::player::Player,
+        replay,
+    };
+
+    /// How long a test waits for anything sent over localhost.
+    const DEADLINE: Duration = Duration::from_secs(5);
+
+    /// Calls `poll` until it returns something, failing after `DEADLINE`.
+    fn until<T>(mut poll: impl FnMut() -> Option<T>) -> T {
+        let deadline = Instant::now() + DEADLINE;
+        loop {
+            if let Some(value) = poll() {
+                return value;
+            }
+            assert!(Instant::now() < deadline, "nothing arrived in time");
+            thread::yield_now();
+        }
+    }
+
+    #[test]
+    fn test_game_over_localhost() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let joining = thread::spawn(move || Guest::join(addr, "guest").unwrap());
+        let (mut host, name) = Host::accept(listener, DEADLINE).unwrap();
+        assert_eq!(name, "guest");
+
+        let mut game = Game::with_players(vec![Player::new("host"), Player::new(name)]);
+        host.welcome(&game).unwrap();
+        let (mut guest, replay) = joining.join().unwrap();
+        assert_eq!(guest.seat, Some(1));
+        let mut copy = replay.game_at(replay.len()).unwrap();
+        assert_eq!(copy.players()[1].name, "guest");
+
+        let mut dice = ScriptedRoller::new([2, 2, 2, 5, 6, 4, 4, 4, 4, 4, 1, 2, 3, 4, 5]);
+        game.roll(&mut dice).unwrap();
+        game.score(RollType::Twos).unwrap();
+        host.broadcast(&game);
+
+        // the guest's moves go through the host
+        guest.send(&Command::Score { column: 0, category: RollType::Fours });
+        guest.send(&Command::Roll);
+        guest.send(&Command::Score { column: 0, category: RollType::Fours });
+        let mut commands = Vec::new();
+        until(|| {
+            commands.extend(host.commands());
+            (commands.len() >= 3).then_some(())
+        });
+        assert_eq!(commands.len(), 3);
+        let results: Vec<_> = commands
+            .iter()
+            .map(|(seat, command)| apply_command(&mut game, *seat, command, &mut dice))
+            .collect();
+        assert!(results[0].is_err());
+        assert!(results[1..].iter().all(Result::is_ok));
+        assert!(apply_command(&mut game, 1, &Command::Roll, &mut dice).is_err());
+        host.broadcast(&game);
+
+        // two steps for each turn
+        for _ in 0..4 {
+            let HostMessage::Event { event } = guest.connection.recv_timeout(DEADLINE).unwrap() else {
+                panic!("expected an event");
+            };
+            replay::apply(&mut copy, &event).unwrap();
+        }
+        assert!(guest.messages().is_empty());
+        assert_eq!(copy.players()[0].rolls().roll(RollType::Twos).score, Some(6));
+        assert_eq!(copy.players()[1].rolls().roll(RollType::Fours).score, Some(20));
+        assert_eq!(copy.current_player_index(), 0);
+
+        // a spectator starts from the game so far and follows it from there
+        let watching = thread::spawn(move || Guest::watch(addr, "spectator").unwrap());
+        let admitted = until(|| Some(host.admit_spectators(&game)).filter(|names| !names.is_empty()));
+        assert_eq!(admitted, vec![String::from("spectator")]);
+        let (mut spectator, replay) = watching.join().unwrap();
+        assert_eq!(spectator.seat, None);
+        let mut watched = replay.game_at(replay.len()).unwrap();
+        assert_eq!(watched.players()[1].rolls().roll(RollType::Fours).score, Some(20));
+
+        game.roll(&mut dice).unwrap();
+        host.broadcast(&game);
+        let HostMessage::Event { event } = spectator.connection.recv_timeout(DEADLINE).unwrap() else {
+            panic!("expected an event");
+        };
+        replay::apply(&mut watched, &event).unwrap();
+        assert_eq!(watched.roll_count(), 1);
+
+        // the host turns down a spectator's move rather than passing it on
+        spectator.send(&Command::Roll);
+        let reply = until(|| {
+            assert!(host.commands().is_empty());
+            spectator.connection.try_recv()
+        });
+        assert!(matches!(reply, HostMessage::Rejected { .. }));
+
+        drop(guest);
+        drop(spectator);
+        let mut departed = Vec::new();
+        until(|| {
+            host.commands();
+            departed.extend(host.departed());
+            host.remotes.is_empty().then_some(())
+        });
+        assert_eq!(departed, vec![1]);
+    }
+
+    #[test]
+    fn test_stalled_spectator_is_dropped() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let joining = thread::spawn(move || Guest::join(addr, "guest").unwrap());
+        let (mut host, name) = Host::accept(listener, DEADLINE).unwrap();
+        let mut game = Game::with_players(vec![Player::new("host"), Player::new(name)]);
+        host.welcome(&game).unwrap();
+        let (mut guest, _) = joining.join().unwrap();
+
+        // a spectator that never reads what it is sent
+        let mut stalled = TcpStream::connect(addr).unwrap();
+        stalled.write_all(b"{\"type\":\"watch\",\"name\":\"stalled\"}\n").unwrap();
+        until(|| Some(host.admit_spectators(&game)).filter(|names| !names.is_empty()));
+
+        game.roll(&mut ScriptedRoller::new([1, 2, 3, 4, 5])).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(60);
+        while host.remotes.len() > 1 {
+            for _ in 0..1000 {
+                game.hold(0).unwrap();
+            }
+            let started = Instant::now();
+            host.broadcast(&game);
+            assert!(started.elapsed() < WRITE_TIMEOUT + DEADLINE, "a send blocked the host");
+            assert!(host.departed().is_empty());
+            assert!(Instant::now() < deadline, "the spectator was never dropped");
+        }
+        assert_eq!(host.remotes[0].seat, Some(1));
+
+        // the player still gets every step
+        let sent = game.events().unwrap().len();
+        let mut received = 0;
+        until(|| {
+            received += guest.messages().len();
+            (received == sent).then_some(())
+        });
+        drop(stalled);
+    }
+
+    #[test]
+    fn test_stale_pending_connection_is_dropped() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let joining = thread::spawn(move || Guest::join(addr, "guest").unwrap());
+        let (mut host, name) = Host::accept(listener, DEADLINE).unwrap();
+        let game = Game::with_players(vec![Player::new("host"), Player::new(name)]);
+        host.welcome(&game).unwrap();
+        joining.join().unwrap();
+
+        // a connection that never says who it is
+        let _silent = TcpStream::connect(addr).unwrap();
+        until(|| {
+            host.admit_spectators(&game);
+            (!host.pending.is_empty()).then_some(())
+        });
+        for (deadline, _) in &mut host.pending {
+            *deadline = Instant::now();
+        }
+        assert!(host.admit_spectators(&game).is_empty());
+        assert!(host.pending.is_empty());
+    }
+
+    #[test]
+    fn test_watcher_before_guest() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let accepting = thread::spawn(move || Host::accept(listener, DEADLINE).unwrap());
+
+        // a port probe and a spectator come before the player
+        drop(TcpStream::connect(addr).unwrap());
+        let watching = thread::spawn(move || Guest::watch(addr, "spectator").unwrap());
+        thread::sleep(ACCEPT_POLL * 4);
+        let joining = thread::spawn(move || Guest::join(addr, "guest").unwrap());
+
+        let (mut host, name) = accepting.join().unwrap();
+        assert_eq!(name, "guest");
+        let game = Game::with_players(vec![Player::new("host"), Player::new(name)]);
+        host.welcome(&game).unwrap();
+        let (guest, _) = joining.join().unwrap();
+        assert_eq!(guest.seat, Some(1));
+        let (spectator, _) = watching.join().unwrap();
+        assert_eq!(spectator.seat, None);
+        assert_eq!(host.admit_spectators(&game), vec![String::from("spectator")]);
+    }
+
+    #[test]
+    fn test_accept_gives_up() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let err = Host::accept(listener, Duration::from_millis(100)).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    }
+
+    #[test]
+    fn test_overlong_line_hangs_up() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut peer = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let mut connection = Connection::<Command>::new(listener.accept().unwrap().0).unwrap();
+        // the connection stops reading partway, so the rest may never be taken
+        thread::spawn(move || {
+            let _ = peer.write_all(&[b' '; MAX_LINE_LEN + 1]);
+        });
+        let err = connection.recv_timeout(DEADLINE).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionAborted);
+        assert!(connection.is_closed());
+    }
+}
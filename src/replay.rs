@@ -0,0 +1,350 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bot::Difficulty,
+    game::{Event, Game, GameError, mode::Mode, roller::ScriptedRoller},
+    model::{house_rules::HouseRules, player::Player, ruleset::Ruleset},
+    storage,
+};
+
+/// Bumped whenever the replay format changes.
+pub const REPLAY_VERSION: u32 = 1;
+/// How many recorded games are kept, the oldest making way for new ones.
+pub const MAX_REPLAYS: usize = 200;
+/// Playback speeds, as multiples of one step per `STEP_INTERVAL`.
+pub const SPEEDS: [f32; 5] = [0.5, 1.0, 2.0, 4.0, 8.0];
+const STEP_INTERVAL: Duration = Duration::from_millis(1200);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Seat {
+    pub name: String,
+    #[serde(default)]
+    pub bot: Option<Difficulty>,
+}
+
+/// A whole game as a list of events, enough to play it back.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    /// The seed the dice were rolled with, if the game was seeded.
+    pub seed: Option<u64>,
+    pub ruleset: Ruleset,
+    #[serde(default)]
+    pub house_rules: HouseRules,
+    #[serde(default)]
+    pub mode: Mode,
+    pub players: Vec<Seat>,
+    pub events: Vec<Event>,
+}
+
+impl Replay {
+    /// The recording of `game`, unless it was resumed from a save that
+    /// predates recording.
+    pub fn from_game(game: &Game, seed: Option<u64>) -> Option<Replay> {
+        Some(Replay {
+            version: REPLAY_VERSION,
+            seed,
+            ruleset: game.ruleset(),
+            house_rules: game.house_rules(),
+            mode: game.mode(),
+            players: game
+                .players()
+                .iter()
+                .map(|p| Seat {
+                    name: p.name.clone(),
+                    bot: p.bot,
+                })
+                .collect(),
+            events: game.events()?.to_vec(),
+        })
+    }
+
+    /// Reads a replay and checks that it plays back.
+    pub fn load(path: &Path) -> io::Result<Replay> {
+        let invalid = |err: String| io::Error::new(io::ErrorKind::InvalidData, err);
+        let contents = fs::read_to_string(path)?;
+        let replay: Replay = serde_json::from_str(&contents).map_err(|err| invalid(err.to_string()))?;
+        if replay.version != REPLAY_VERSION {
+            return Err(invalid(format!("unsupported replay version {}", replay.version)));
+        }
+        replay.game_at(replay.len()).map_err(invalid)?;
+        Ok(replay)
+    }
+
+    pub fn store(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_vec_pretty(self).map_err(io::Error::other)?;
+        storage::write_atomic(path, &json)
+    }
+
+    /// Stores a finished game in the replay directory, named after the time
+    /// it was stored, and drops the oldest games beyond `MAX_REPLAYS`.
+    pub fn store_new(&self) -> io::Result<Option<PathBuf>> {
+        let Some(dir) = storage::data_dir() else {
+            return Ok(None);
+        };
+        self.store_in(&dir.join("replays"), MAX_REPLAYS).map(Some)
+    }
+
+    fn store_in(&self, dir: &Path, keep: usize) -> io::Result<PathBuf> {
+        fs::create_dir_all(dir)?;
+        let stamp = Local::now().format("%Y%m%d-%H%M%S").to_string();
+        // games ending in the same second get a number after the time
+        let mut number = 1;
+        let path = loop {
+            let name = match number {
+                1 => format!("{stamp}.json"),
+                n => format!("{stamp}-{n}.json"),
+            };
+            let path = dir.join(name);
+            match fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => break path,
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => number += 1,
+                Err(err) => return Err(err),
+            }
+        };
+        self.store(&path)?;
+        prune(dir, keep)?;
+        Ok(path)
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// The game as it stood after the first `step` events.
+    pub fn game_at(&self, step: usize) -> Result<Game, String> {
+        if self.players.is_empty() {
+            return Err(String::from("the replay has no players"));
+        }
+        let players = self
+            .players
+            .iter()
+            .map(|seat| match seat.bot {
+                Some(difficulty) => Player::bot(&seat.name, difficulty),
+                None => Player::new(&seat.name),
+            })
+            .collect();
+        let mut game = Game::with_players(players)
+            .with_ruleset(self.ruleset)
+            .with_house_rules(self.house_rules)
+            .with_mode(self.mode);
+        for (i, event) in self.events.iter().take(step).enumerate() {
+            apply(&mut game, event).map_err(|err| format!("event {}: {err}", i + 1))?;
+        }
+        Ok(game)
+    }
+}
+
+/// Plays `event` on `game`, checking it is what was recorded.
+pub(crate) fn apply(game: &mut Game, event: &Event) -> Result<(), String> {
+    match event {
+        Event::Roll { dice } => {
+            if dice.len() != game.dice().len() || dice.iter().any(|v| !(1..=6).contains(v)) {
+                return Err(format!("invalid dice {dice:?}"));
+            }
+            let rolled: Vec<u8> = game
+                .dice()
+                .iter()
+                .zip(dice)
+                .filter(|(die, _)| !die.held)
+                .map(|(_, value)| *value)
+                .collect();
+            game.roll(&mut ScriptedRoller::new(rolled)).map_err(|err| err.to_string())?;
+            if game.dice().iter().map(|d| d.value).ne(dice.iter().copied()) {
+                return Err(String::from("a held die changed value"));
+            }
+            Ok(())
+        }
+        Event::Hold { die } => game.hold(*die).map_err(|err| err.to_string()),
+        Event::Score {
+            category,
+            points,
+            column,
+        } => match game.score_in(*column, *category) {
+            Ok(score) if score == *points => Ok(()),
+            Ok(score) => Err(format!("{category:?} scored {score}, not {points}")),
+            Err(err) => Err(err.to_string()),
+        },
+        Event::Undo => {
+            let seat = game.last_scorer().ok_or_else(|| GameError::NothingToUndo.to_string())?;
+            game.undo(seat).map_err(|err| err.to_string())
+        }
+        Event::Forfeit => game.forfeit().map_err(|err| err.to_string()),
+    }
+}
+
+/// Removes all but the newest `keep` replays in `dir`.
+fn prune(dir: &Path, keep: usize) -> io::Result<()> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            paths.push(path);
+        }
+    }
+    if paths.len() <= keep {
+        return Ok(());
+    }
+    // oldest first, then in the order games ending in the same second were numbered
+    paths.sort_by_cached_key(|path| {
+        let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+        (modified, path.as_os_str().len(), path.clone())
+    });
+    let excess = paths.len() - keep;
+    for path in &paths[..excess] {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Where playback of a replay is, and how fast it moves on.
+pub struct Playback {
+    pub replay: Replay,
+    pub step: usize,
+    pub paused: bool,
+    speed: usize,
+    next_step_at: Instant,
+}
+
+impl Playback {
+    pub fn new(replay: Replay) -> Playback {
+        Playback {
+            replay,
+            step: 0,
+            paused: false,
+            speed: 1,
+            next_step_at: Instant::now() + STEP_INTERVAL,
+        }
+    }
+
+    pub fn speed(&self) -> f32 {
+        SPEEDS[self.speed]
+    }
+
+    pub fn interval(&self) -> Duration {
+        STEP_INTERVAL.div_f32(self.speed())
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = (self.speed + 1).min(SPEEDS.len() - 1);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = self.speed.saturating_sub(1);
+    }
+
+    /// Pauses or resumes, resuming with a full step's wait.
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.next_step_at = Instant::now() + self.interval();
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.step == self.replay.len()
+    }
+
+    /// Moves one step on when it is time to, returning whether it did.
+    pub fn advance_if_due(&mut self, now: Instant) -> bool {
+        if self.paused || self.is_at_end() || now < self.next_step_at {
+            return false;
+        }
+        self.step_forward();
+        true
+    }
+
+    pub fn step_forward(&mut self) {
+        self.step = (self.step + 1).min(self.replay.len());
+        self.next_step_at = Instant::now() + self.interval();
+    }
+
+    pub fn step_back(&mut self) {
+        self.step = self.step.saturating_sub(1);
+        self.next_step_at = Instant::now() + self.interval();
+    }
+
+    /// The event that led to the current step.
+    pub fn last_event(&self) -> Option<&Event> {
+        self.step.checked_sub(1).map(|i| &self.replay.events[i])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::roll::RollType;
+
+    fn recorded_game() -> Game {
+        let mut game = Game::with_players(vec![Player::new("a"), Player::bot("b", Difficulty::Greedy)]);
+        let mut dice = ScriptedRoller::new([3, 3, 3, 1, 2, 5, 6, 4, 4, 4, 4, 4, 2, 2, 2]);
+        game.roll(&mut dice).unwrap();
+        game.hold(0).unwrap();
+        game.hold(1).unwrap();
+        game.hold(2).unwrap();
+        game.roll(&mut dice).unwrap();
+        game.score(RollType::Chance).unwrap();
+        game.undo(0).unwrap();
+        game.score(RollType::Threes).unwrap();
+        game.roll(&mut dice).unwrap();
+        game.score(RollType::Yahtzee).unwrap();
+        game
+    }
+
+    #[test]
+    fn test_replay_round_trip() {
+        let game = recorded_game();
+        let replay = Replay::from_game(&game, Some(9)).unwrap();
+        let json = serde_json::to_string(&replay).unwrap();
+        let parsed: Replay = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, replay);
+
+        let end = parsed.game_at(parsed.len()).unwrap();
+        assert_eq!(end.players()[0].rolls().roll(RollType::Threes).score, Some(9));
+        assert_eq!(end.players()[0].rolls().roll(RollType::Chance).score, None);
+        assert_eq!(end.players()[1].rolls().roll(RollType::Yahtzee).score, Some(50));
+
+        let midway = parsed.game_at(5).unwrap();
+        assert_eq!(midway.roll_count(), 2);
+        assert!(midway.dice()[2].held);
+    }
+
+    #[test]
+    fn test_store_keeps_the_newest() {
+        let dir = std::env::temp_dir().join(format!("rahtzee-replays-{}", std::process::id()));
+        let replay = Replay::from_game(&recorded_game(), None).unwrap();
+        let paths: Vec<PathBuf> = (0..3).map(|_| replay.store_in(&dir, 2).unwrap()).collect();
+        // stored within the same second, none of them overwrites another
+        assert_ne!(paths[0], paths[1]);
+        assert_ne!(paths[1], paths[2]);
+        assert!(!paths[0].exists());
+        assert_eq!(Replay::load(&paths[2]).unwrap(), replay);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_tampered_replay_is_rejected() {
+        let mut replay = Replay::from_game(&recorded_game(), None).unwrap();
+        replay.events[4] = Event::Roll {
+            dice: vec![1, 3, 3, 5, 6],
+        };
+        assert!(replay.game_at(replay.len()).is_err());
+    }
+
+    #[test]
+    fn test_resumed_games_without_events_are_not_replayable() {
+        let game = recorded_game();
+        let restored = Game::restore(game.players().to_vec(), 0, game.dice().to_vec(), 0);
+        assert!(Replay::from_game(&restored, None).is_none());
+    }
+}
@@ -0,0 +1,119 @@
+use std::{fs, io, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::{Event, Game, mode::Mode},
+    model::{die::DieFace, player::Player},
+    storage,
+};
+
+/// Bumped whenever the save format changes; older saves are ignored.
+pub const SAVE_VERSION: u32 = 4;
+
+#[derive(Serialize, Deserialize)]
+pub struct SaveData {
+    pub version: u32,
+    pub mode: Mode,
+    pub players: Vec<Player>,
+    pub current_player: usize,
+    pub dice_faces: Vec<DieFace>,
+    pub roll_count: u8,
+    /// Older saves were written before undo existed.
+    #[serde(default)]
+    pub undo_count: u32,
+    /// Missing from saves written before games were recorded.
+    #[serde(default)]
+    pub events: Option<Vec<Event>>,
+}
+
+impl SaveData {
+    pub fn from_game(game: &Game) -> SaveData {
+        SaveData {
+            version: SAVE_VERSION,
+            mode: game.mode(),
+            players: game.players().to_vec(),
+            current_player: game.current_player_index(),
+            dice_faces: game.dice().to_vec(),
+            roll_count: game.roll_count(),
+            undo_count: game.undo_count(),
+            events: game.events().map(<[Event]>::to_vec),
+        }
+    }
+
+    pub fn into_game(self) -> Game {
+        let game = Game::restore(self.players, self.current_player, self.dice_faces, self.roll_count)
+            .with_mode(self.mode)
+            .with_undo_count(self.undo_count);
+        match self.events {
+            Some(events) => game.with_events(events),
+            None => game,
+        }
+    }
+
+    fn parse(contents: &str) -> Option<SaveData> {
+        serde_json::from_str(contents)
+            .ok()
+            .filter(|save: &SaveData| save.version == SAVE_VERSION)
+    }
+}
+
+fn save_path() -> Option<PathBuf> {
+    storage::data_dir().map(|dir| dir.join("save.json"))
+}
+
+/// The saved game, if there is one in a format this version understands.
+pub fn load() -> Option<SaveData> {
+    let contents = fs::read_to_string(save_path()?).ok()?;
+    SaveData::parse(&contents)
+}
+
+pub fn store(save: &SaveData) -> io::Result<()> {
+    let Some(path) = save_path() else {
+        return Ok(());
+    };
+    let json = serde_json::to_vec_pretty(save).map_err(io::Error::other)?;
+    storage::write_atomic(&path, &json)
+}
+
+pub fn clear() -> io::Result<()> {
+    let Some(path) = save_path() else {
+        return Ok(());
+    };
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::roll::RollType;
+
+    #[test]
+    fn test_round_trip() {
+        let mut players = vec![Player::new("a"), Player::new("b")];
+        players[1].rolls_mut().roll_mut(RollType::FullHouse).score = Some(25);
+        let mut dice: Vec<DieFace> = [2, 2, 3, 3, 3].map(DieFace::new).to_vec();
+        dice[1].held = true;
+        let game = Game::restore(players, 1, dice, 2);
+
+        let json = serde_json::to_string(&SaveData::from_game(&game)).unwrap();
+        let restored = SaveData::parse(&json).unwrap().into_game();
+        assert_eq!(restored.current_player().name, "b");
+        assert_eq!(restored.rolls().roll(RollType::FullHouse).score, Some(25));
+        assert_eq!(restored.roll_count(), 2);
+        assert!(restored.dice()[1].held);
+        assert_eq!(restored.dice()[4].value, 3);
+    }
+
+    #[test]
+    fn test_ignores_other_versions() {
+        let mut save = SaveData::from_game(&Game::new());
+        save.version = SAVE_VERSION + 1;
+        let json = serde_json::to_string(&save).unwrap();
+        assert!(SaveData::parse(&json).is_none());
+        assert!(SaveData::parse("{ not json").is_none());
+    }
+}
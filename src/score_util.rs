@@ -1,33 +1,99 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, ops::RangeInclusive};
 
-use crate::{app::DieFace, model::roll::{Roll, RollType}};
+use crate::model::{die::DieFace, roll::{AllRolls, Roll, RollType}, ruleset::Ruleset};
 
-pub fn calc_score(roll: Roll, faces: &Vec<DieFace>) -> u32 {
-    match roll.roll_type {
-        RollType::Ones => calc_score_for_num_type(1, faces),
-        RollType::Twos => calc_score_for_num_type(2, faces),
-        RollType::Threes => calc_score_for_num_type(3, faces),
-        RollType::Fours => calc_score_for_num_type(4, faces),
-        RollType::Fives => calc_score_for_num_type(5, faces),
-        RollType::Sixes => calc_score_for_num_type(6, faces),
-        RollType::ThreeOfAKind => calc_score_for_x_of_a_kind(3, faces),
-        RollType::FourOfAKind => calc_score_for_x_of_a_kind(4, faces),
-        RollType::FullHouse => calc_score_for_full_house(faces),
-        RollType::SmallStraight => calc_score_for_straight(4, faces),
-        RollType::LargeStraight => calc_score_for_straight(5, faces),
-        RollType::Chance => calc_score_for_chance(faces),
-        RollType::Yahtzee => calc_score_for_yahtzee(roll, faces),
+/// Score `roll` would receive for `faces`, given the rest of the scorecard
+/// and the ruleset and house rules it is kept under.
+///
+/// A Yahtzee rolled after the Yahtzee box is filled is a Joker: any lower
+/// slot it is allowed into scores its full fixed value.
+pub fn calc_score(roll: Roll, rolls: &AllRolls, faces: &[DieFace]) -> u32 {
+    let fixed = rolls.ruleset.fixed_points(roll.roll_type);
+    if let Some(points) = fixed
+        && is_joker(rolls, faces)
+    {
+        return points;
     }
+    let house = rolls.house_rules;
+    // rules without a fixed Full House score a Joker there like the house rule does
+    if roll.roll_type == RollType::FullHouse
+        && (house.full_house_yahtzee && is_yahtzee(faces) || is_joker(rolls, faces))
+    {
+        return match rolls.ruleset {
+            Ruleset::Standard => fixed.unwrap_or(0),
+            Ruleset::Yacht => face_total(faces),
+            Ruleset::Maxi => 5 * u32::from(faces[0].value),
+        };
+    }
+    match (rolls.ruleset, roll.roll_type) {
+        (Ruleset::Yacht, RollType::FourOfAKind) => calc_score_for_matching(4, faces),
+        (Ruleset::Yacht, RollType::FullHouse) if calc_score_for_full_house(faces) > 0 => face_total(faces),
+        (Ruleset::Yacht, RollType::FullHouse) => 0,
+        (Ruleset::Yacht, RollType::SmallStraight) => calc_score_for_run(1..=5, fixed.unwrap_or(0), faces),
+        (Ruleset::Yacht, RollType::LargeStraight) => calc_score_for_run(2..=6, fixed.unwrap_or(0), faces),
+        (Ruleset::Maxi, RollType::ThreeOfAKind) => calc_score_for_matching(3, faces),
+        (Ruleset::Maxi, RollType::FourOfAKind) => calc_score_for_matching(4, faces),
+        (Ruleset::Maxi, RollType::FullHouse) => calc_score_for_sets(3, 2, faces),
+        (Ruleset::Maxi, RollType::SmallStraight) => calc_score_for_run(1..=5, fixed.unwrap_or(0), faces),
+        (Ruleset::Maxi, RollType::LargeStraight) => calc_score_for_run(2..=6, fixed.unwrap_or(0), faces),
+        (_, RollType::Ones) => calc_score_for_num_type(1, faces),
+        (_, RollType::Twos) => calc_score_for_num_type(2, faces),
+        (_, RollType::Threes) => calc_score_for_num_type(3, faces),
+        (_, RollType::Fours) => calc_score_for_num_type(4, faces),
+        (_, RollType::Fives) => calc_score_for_num_type(5, faces),
+        (_, RollType::Sixes) => calc_score_for_num_type(6, faces),
+        (_, RollType::OnePair) => calc_score_for_pairs(1, faces),
+        (_, RollType::TwoPairs) => calc_score_for_pairs(2, faces),
+        (_, RollType::ThreePairs) => calc_score_for_pairs(3, faces),
+        (_, RollType::ThreeOfAKind) => calc_score_for_x_of_a_kind(3, faces),
+        (_, RollType::FourOfAKind) => calc_score_for_x_of_a_kind(4, faces),
+        (_, RollType::FullHouse) => calc_score_for_full_house(faces),
+        (_, RollType::Castle) => calc_score_for_sets(3, 3, faces),
+        (_, RollType::Tower) => calc_score_for_sets(4, 2, faces),
+        // exactly four faces in a row, the fifth die matching one of them
+        (_, RollType::SmallStraight) if house.exact_small_straight && face_counts(faces).len() != 4 => 0,
+        (_, RollType::SmallStraight) => calc_score_for_straight(4, faces),
+        (_, RollType::LargeStraight) => calc_score_for_straight(5, faces),
+        (_, RollType::FullStraight) => calc_score_for_run(1..=6, fixed.unwrap_or(0), faces),
+        (_, RollType::Chance) => calc_score_for_chance(faces),
+        (_, RollType::Yahtzee) => calc_score_for_yahtzee(roll, rolls, faces),
+    }
+}
+
+/// Whether `faces` is a Yahtzee rolled after the Yahtzee box was filled,
+/// under rules that make it a Joker.
+pub fn is_joker(rolls: &AllRolls, faces: &[DieFace]) -> bool {
+    rolls.jokers()
+        && rolls.roll(RollType::Yahtzee).score.is_some()
+        && is_yahtzee(faces)
 }
 
-fn calc_score_for_num_type(num: u8, faces: &Vec<DieFace>) -> u32 {
+/// Whether the forced-Joker rules allow `roll_type` to be scored.
+///
+/// A Joker must go into the matching upper slot if it is open, otherwise
+/// into any open lower slot, and only then into an open upper slot for zero.
+pub fn can_score(roll_type: RollType, rolls: &AllRolls, faces: &[DieFace]) -> bool {
+    if !is_joker(rolls, faces) {
+        return true;
+    }
+
+    let upper = rolls.upper_roll_for(faces[0].value);
+    if upper.score.is_none() {
+        return upper.roll_type == roll_type;
+    }
+
+    let lower_open = rolls.iter().any(|r| !r.roll_type.is_upper() && r.score.is_none());
+    roll_type.is_upper() != lower_open
+}
+
+fn calc_score_for_num_type(num: u8, faces: &[DieFace]) -> u32 {
     faces
         .iter()
         .filter(|f| f.value == num)
         .fold(0u32, |sum, f| sum + f.value as u32)
 }
 
-fn calc_score_for_x_of_a_kind(num: u8, faces: &Vec<DieFace>) -> u32 {
+fn calc_score_for_x_of_a_kind(num: u8, faces: &[DieFace]) -> u32 {
     let counts = face_counts(faces);
     let applies = counts.iter().any(|(_, count)| *count >= num);
 
@@ -37,7 +103,46 @@ fn calc_score_for_x_of_a_kind(num: u8, faces: &Vec<DieFace>) -> u32 {
     }
 }
 
-fn calc_score_for_full_house(faces: &Vec<DieFace>) -> u32 {
+/// Just the `count` matching dice of the highest face showing that many.
+fn calc_score_for_matching(count: u8, faces: &[DieFace]) -> u32 {
+    face_counts(faces)
+        .into_iter()
+        .filter(|(_, n)| *n >= count)
+        .map(|(value, _)| u32::from(count) * u32::from(value))
+        .max()
+        .unwrap_or(0)
+}
+
+/// The `count` highest pairs of different faces.
+fn calc_score_for_pairs(count: usize, faces: &[DieFace]) -> u32 {
+    let mut pairs: Vec<u32> = face_counts(faces)
+        .into_iter()
+        .filter(|(_, n)| *n >= 2)
+        .map(|(value, _)| 2 * u32::from(value))
+        .collect();
+    if pairs.len() < count {
+        return 0;
+    }
+    pairs.sort_unstable_by(|a, b| b.cmp(a));
+    pairs[..count].iter().sum()
+}
+
+/// A set of `big` and a set of `small` of another face, counting just
+/// those dice.
+fn calc_score_for_sets(big: u8, small: u8, faces: &[DieFace]) -> u32 {
+    let counts = face_counts(faces);
+    let mut best = 0;
+    for (a, a_count) in &counts {
+        for (b, b_count) in &counts {
+            if a != b && *a_count >= big && *b_count >= small {
+                best = best.max(u32::from(big * a + small * b));
+            }
+        }
+    }
+    best
+}
+
+fn calc_score_for_full_house(faces: &[DieFace]) -> u32 {
     let counts = face_counts(faces);
     if counts.iter().any(|(_, count)| *count == 2) && counts.iter().any(|(_, count)| *count == 3) {
         25
@@ -46,22 +151,22 @@ fn calc_score_for_full_house(faces: &Vec<DieFace>) -> u32 {
     }
 }
 
-fn calc_score_for_straight(count: u8, faces: &Vec<DieFace>) -> u32 {
-    let mut sorted = faces.clone();
-    sorted.sort_by(|a, b| a.value.cmp(&b.value));
+fn calc_score_for_straight(count: u8, faces: &[DieFace]) -> u32 {
+    let mut sorted = faces.to_vec();
+    sorted.sort_by_key(|f| f.value);
     
     let mut max_run = 1;
     let mut current_run = 1;
     let mut last_value = sorted[0].value;
     
-    for i in 1..sorted.len() {
-        if sorted[i].value == last_value + 1 {
+    for face in sorted.iter().skip(1) {
+        if face.value == last_value + 1 {
             current_run += 1;
             max_run = max_run.max(current_run);
-        } else if sorted[i].value != last_value {
+        } else if face.value != last_value {
             current_run = 1;
         }
-        last_value = sorted[i].value;
+        last_value = face.value;
     }
 
     if max_run >= count {
@@ -75,41 +180,44 @@ fn calc_score_for_straight(count: u8, faces: &Vec<DieFace>) -> u32 {
     }
 }
 
-fn calc_score_for_chance(faces: &Vec<DieFace>) -> u32 {
+/// A straight covering exactly the faces in `run`, worth `points`.
+fn calc_score_for_run(mut run: RangeInclusive<u8>, points: u32, faces: &[DieFace]) -> u32 {
+    if run.all(|value| faces.iter().any(|f| f.value == value)) { points } else { 0 }
+}
+
+fn calc_score_for_chance(faces: &[DieFace]) -> u32 {
     face_total(faces)
 }
 
-fn calc_score_for_yahtzee(roll: Roll, faces: &Vec<DieFace>) -> u32 {
-    if is_yahtzee(faces) {
-        let current = roll.score.unwrap_or(0);
-        if current > 0 {
-            current + 100
-        } else {
-            50
-        }
-    } else {
-        roll.score.unwrap_or(0)
+/// A scratched Yahtzee box stays at 0 and earns no bonus.
+fn calc_score_for_yahtzee(roll: Roll, rolls: &AllRolls, faces: &[DieFace]) -> u32 {
+    match (roll.score, is_yahtzee(faces), rolls.yahtzee_bonus()) {
+        (None, true, _) => rolls.ruleset.yahtzee_points(),
+        (Some(current), true, Some(bonus)) if current > 0 => current + bonus,
+        (current, _, _) => current.unwrap_or(0),
     }
 }
 
-pub fn is_yahtzee(faces: &Vec<DieFace>) -> bool {
-    face_counts(faces).iter().any(|(_, count)| *count == 5)
+/// Whether every die shows the same face.
+pub fn is_yahtzee(faces: &[DieFace]) -> bool {
+    face_counts(faces).len() == 1
 }
 
-fn face_counts(faces: &Vec<DieFace>) -> HashMap<u8, u8> {
+fn face_counts(faces: &[DieFace]) -> HashMap<u8, u8> {
     faces.iter().fold(HashMap::new(), |mut map, f| {
         *map.entry(f.value).or_insert(0) += 1;
         map
     })
 }
 
-fn face_total(faces: &Vec<DieFace>) -> u32 {
+fn face_total(faces: &[DieFace]) -> u32 {
     faces.iter().fold(0u32, |tot, f| tot + f.value as u32)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::house_rules::HouseRules;
 
     #[test]
     fn test_small_straight() {
@@ -183,4 +291,145 @@ mod tests {
         ];
         assert_eq!(calc_score_for_straight(4, &dice), 30);
     }
+
+    fn yahtzee_of(value: u8) -> Vec<DieFace> {
+        vec![DieFace::new(value); 5]
+    }
+
+    fn with_yahtzee_scored() -> AllRolls {
+        let mut rolls = AllRolls::new();
+        rolls.roll_mut(RollType::Yahtzee).score = Some(50);
+        rolls
+    }
+
+    #[test]
+    fn test_joker_full_value_in_lower_slots() {
+        let mut rolls = with_yahtzee_scored();
+        rolls.roll_mut(RollType::Fours).score = Some(12);
+        let dice = yahtzee_of(4);
+        assert_eq!(calc_score(*rolls.roll(RollType::FullHouse), &rolls, &dice), 25);
+        assert_eq!(calc_score(*rolls.roll(RollType::SmallStraight), &rolls, &dice), 30);
+        assert_eq!(calc_score(*rolls.roll(RollType::LargeStraight), &rolls, &dice), 40);
+        assert_eq!(calc_score(*rolls.roll(RollType::ThreeOfAKind), &rolls, &dice), 20);
+    }
+
+    #[test]
+    fn test_no_joker_before_yahtzee_scored() {
+        let rolls = AllRolls::new();
+        let dice = yahtzee_of(4);
+        assert_eq!(calc_score(*rolls.roll(RollType::FullHouse), &rolls, &dice), 0);
+        assert_eq!(calc_score(*rolls.roll(RollType::LargeStraight), &rolls, &dice), 0);
+        assert!(can_score(RollType::Chance, &rolls, &dice));
+    }
+
+    #[test]
+    fn test_joker_forced_into_matching_upper_slot() {
+        let rolls = with_yahtzee_scored();
+        let dice = yahtzee_of(3);
+        assert!(can_score(RollType::Threes, &rolls, &dice));
+        assert!(!can_score(RollType::FullHouse, &rolls, &dice));
+        assert!(!can_score(RollType::Ones, &rolls, &dice));
+    }
+
+    #[test]
+    fn test_joker_lower_slots_before_upper_zero() {
+        let mut rolls = with_yahtzee_scored();
+        rolls.roll_mut(RollType::Threes).score = Some(9);
+        let dice = yahtzee_of(3);
+        assert!(can_score(RollType::LargeStraight, &rolls, &dice));
+        assert!(!can_score(RollType::Ones, &rolls, &dice));
+
+        for roll in rolls.iter_mut().filter(|r| !r.roll_type.is_upper() && r.roll_type != RollType::Yahtzee) {
+            roll.score = Some(0);
+        }
+        assert!(can_score(RollType::Ones, &rolls, &dice));
+        assert_eq!(calc_score(*rolls.roll(RollType::Ones), &rolls, &dice), 0);
+    }
+
+    #[test]
+    fn test_yacht_scoring() {
+        let mut rolls = AllRolls::with_ruleset(Ruleset::Yacht);
+        let score = |rolls: &AllRolls, roll_type, values: [u8; 5]| {
+            let dice = values.map(DieFace::new);
+            calc_score(*rolls.roll(roll_type), rolls, &dice)
+        };
+        assert!(!rolls.has(RollType::ThreeOfAKind));
+        assert_eq!(score(&rolls, RollType::FourOfAKind, [5, 5, 2, 5, 5]), 20);
+        assert_eq!(score(&rolls, RollType::FullHouse, [6, 6, 2, 2, 6]), 22);
+        assert_eq!(score(&rolls, RollType::SmallStraight, [5, 3, 1, 2, 4]), 30);
+        assert_eq!(score(&rolls, RollType::SmallStraight, [2, 3, 4, 5, 6]), 0);
+        assert_eq!(score(&rolls, RollType::LargeStraight, [2, 3, 4, 5, 6]), 30);
+        assert_eq!(score(&rolls, RollType::LargeStraight, [1, 3, 4, 5, 6]), 0);
+
+        rolls.roll_mut(RollType::Yahtzee).score = Some(50);
+        // no bonus Yacht and no Joker
+        assert_eq!(score(&rolls, RollType::Yahtzee, [3; 5]), 50);
+        assert_eq!(score(&rolls, RollType::FullHouse, [3; 5]), 0);
+        assert!(can_score(RollType::Ones, &rolls, &[3; 5].map(DieFace::new)));
+
+        for roll_type in [RollType::Sixes, RollType::Fives, RollType::Fours] {
+            rolls.roll_mut(roll_type).score = Some(30);
+        }
+        assert_eq!(rolls.bonus_status(), (90, 0));
+    }
+
+    #[test]
+    fn test_house_rules_scoring() {
+        let mut rolls = AllRolls::with_rules(
+            Ruleset::Standard,
+            HouseRules {
+                upper_bonus_threshold: Some(70),
+                yahtzee_bonus: Some(50),
+                full_house_yahtzee: true,
+                exact_small_straight: true,
+                jokers: Some(false),
+                ..HouseRules::default()
+            },
+        );
+        let score = |rolls: &AllRolls, roll_type, values: [u8; 5]| {
+            let dice = values.map(DieFace::new);
+            calc_score(*rolls.roll(roll_type), rolls, &dice)
+        };
+        assert_eq!(score(&rolls, RollType::FullHouse, [4; 5]), 25);
+        assert_eq!(score(&rolls, RollType::SmallStraight, [1, 2, 3, 4, 4]), 30);
+        assert_eq!(score(&rolls, RollType::SmallStraight, [1, 2, 3, 4, 6]), 0);
+        assert_eq!(score(&rolls, RollType::SmallStraight, [1, 2, 3, 4, 5]), 0);
+
+        rolls.roll_mut(RollType::Yahtzee).score = Some(50);
+        assert_eq!(score(&rolls, RollType::Yahtzee, [2; 5]), 100);
+        // a bonus Yahtzee without Jokers goes anywhere, scoring as usual
+        assert!(can_score(RollType::Chance, &rolls, &[2; 5].map(DieFace::new)));
+        assert_eq!(score(&rolls, RollType::LargeStraight, [2; 5]), 0);
+
+        rolls.roll_mut(RollType::Yahtzee).score = Some(150);
+        assert_eq!(rolls.yahtzee_bonus_count(), 2);
+        for roll_type in [RollType::Sixes, RollType::Fives] {
+            rolls.roll_mut(roll_type).score = Some(30);
+        }
+        assert_eq!(rolls.bonus_status(), (60, 0));
+        rolls.roll_mut(RollType::Fours).score = Some(12);
+        assert_eq!(rolls.bonus_status(), (72, 35));
+    }
+
+    #[test]
+    fn test_maxi_scoring() {
+        let rolls = AllRolls::with_ruleset(Ruleset::Maxi);
+        let score = |roll_type, values: [u8; 6]| {
+            let dice = values.map(DieFace::new);
+            calc_score(*rolls.roll(roll_type), &rolls, &dice)
+        };
+        assert_eq!(score(RollType::OnePair, [2, 2, 5, 5, 1, 3]), 10);
+        assert_eq!(score(RollType::TwoPairs, [2, 2, 5, 5, 6, 6]), 22);
+        assert_eq!(score(RollType::TwoPairs, [5, 5, 5, 5, 1, 3]), 0);
+        assert_eq!(score(RollType::ThreePairs, [2, 2, 5, 5, 6, 6]), 26);
+        assert_eq!(score(RollType::ThreeOfAKind, [4, 4, 4, 6, 6, 6]), 18);
+        assert_eq!(score(RollType::FullHouse, [4, 4, 4, 6, 6, 1]), 24);
+        assert_eq!(score(RollType::Castle, [4, 4, 4, 6, 6, 6]), 30);
+        assert_eq!(score(RollType::Tower, [3, 3, 3, 3, 1, 1]), 14);
+        assert_eq!(score(RollType::SmallStraight, [1, 2, 3, 4, 5, 5]), 15);
+        assert_eq!(score(RollType::LargeStraight, [6, 2, 3, 4, 5, 5]), 20);
+        assert_eq!(score(RollType::FullStraight, [6, 2, 3, 4, 5, 1]), 21);
+        assert_eq!(score(RollType::Yahtzee, [2; 6]), 100);
+        assert_eq!(score(RollType::Yahtzee, [2, 2, 2, 2, 2, 1]), 0);
+    }
 }
@@ -0,0 +1,91 @@
+use std::{fmt, fs, io, path::PathBuf, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::storage;
+
+/// How long bots pause between their moves.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BotSpeed {
+    Slow,
+    #[default]
+    Normal,
+    Fast,
+}
+
+impl BotSpeed {
+    pub const ALL: [BotSpeed; 3] = [BotSpeed::Slow, BotSpeed::Normal, BotSpeed::Fast];
+
+    pub fn delay(self) -> Duration {
+        match self {
+            BotSpeed::Slow => Duration::from_millis(1400),
+            BotSpeed::Normal => Duration::from_millis(700),
+            BotSpeed::Fast => Duration::from_millis(200),
+        }
+    }
+}
+
+impl fmt::Display for BotSpeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BotSpeed::Slow => "Slow",
+            BotSpeed::Normal => "Normal",
+            BotSpeed::Fast => "Fast",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The player's preferences, changed on the settings screen and kept in
+/// `settings.toml`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Allows each turn to be undone only once in new games.
+    pub one_undo_per_turn: bool,
+    /// Whether games start with the hint shown.
+    pub hints: bool,
+    pub bot_speed: BotSpeed,
+}
+
+impl Settings {
+    /// Reads the settings, falling back to the defaults if there are none
+    /// or they cannot be read.
+    pub fn load() -> Settings {
+        settings_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| toml::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn store(&self) -> io::Result<()> {
+        let Some(path) = settings_path() else {
+            return Ok(());
+        };
+        let contents = toml::to_string(self).map_err(io::Error::other)?;
+        storage::write_atomic(&path, contents.as_bytes())
+    }
+}
+
+fn settings_path() -> Option<PathBuf> {
+    storage::config_dir().map(|dir| dir.join("settings.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_round_trip() {
+        let settings = Settings {
+            one_undo_per_turn: true,
+            hints: false,
+            bot_speed: BotSpeed::Fast,
+        };
+        let contents = toml::to_string(&settings).unwrap();
+        assert_eq!(toml::from_str::<Settings>(&contents).unwrap(), settings);
+        // settings missing from older files keep their defaults
+        assert_eq!(toml::from_str::<Settings>("hints = true").unwrap().bot_speed, BotSpeed::Normal);
+    }
+}
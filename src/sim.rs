@@ -0,0 +1,238 @@
+use std::{fmt, sync::Arc, thread};
+
+use serde::Serialize;
+
+use crate::{
+    bot::{Bot, Difficulty},
+    game::{Game, roller::SeededRoller},
+    model::roll::{AllRolls, RollType},
+    solver::Solver,
+};
+
+/// Width of a score histogram bucket.
+pub const BUCKET_WIDTH: u32 = 25;
+const BAR_WIDTH: u64 = 40;
+
+pub struct SimConfig {
+    pub games: u64,
+    pub strategy: Difficulty,
+    pub seed: u64,
+    pub threads: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Bucket {
+    pub from: u32,
+    pub to: u32,
+    pub count: u64,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct CategoryAverage {
+    pub category: RollType,
+    pub average: f64,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Report {
+    pub strategy: Difficulty,
+    pub seed: u64,
+    pub games: u64,
+    pub mean: f64,
+    pub stddev: f64,
+    pub min: u32,
+    pub max: u32,
+    pub histogram: Vec<Bucket>,
+    /// Share of games that earned the upper section bonus.
+    pub upper_bonus_rate: f64,
+    /// Share of games with at least one Yahtzee.
+    pub yahtzee_rate: f64,
+    /// Average of each category, the Yahtzee one including bonuses.
+    pub categories: Vec<CategoryAverage>,
+}
+
+/// Running totals, kept as integers so merging the threads' results in any
+/// order gives the same report.
+#[derive(Default)]
+struct Totals {
+    games: u64,
+    sum: u64,
+    sum_squares: u128,
+    min: Option<u32>,
+    max: u32,
+    histogram: Vec<u64>,
+    upper_bonuses: u64,
+    yahtzees: u64,
+    categories: [u64; RollType::STANDARD.len()],
+}
+
+impl Totals {
+    fn add(&mut self, rolls: &AllRolls) {
+        let total = rolls.total_score();
+        self.games += 1;
+        self.sum += u64::from(total);
+        self.sum_squares += u128::from(total) * u128::from(total);
+        self.min = Some(self.min.map_or(total, |min| min.min(total)));
+        self.max = self.max.max(total);
+        let bucket = (total / BUCKET_WIDTH) as usize;
+        if self.histogram.len() <= bucket {
+            self.histogram.resize(bucket + 1, 0);
+        }
+        self.histogram[bucket] += 1;
+        self.upper_bonuses += u64::from(rolls.bonus_status().1 > 0);
+        self.yahtzees += u64::from(rolls.roll(RollType::Yahtzee).score.is_some_and(|s| s >= 50));
+        for (sum, roll_type) in self.categories.iter_mut().zip(RollType::STANDARD) {
+            *sum += u64::from(rolls.roll(roll_type).score.unwrap_or(0));
+        }
+    }
+
+    fn merge(&mut self, other: Totals) {
+        self.games += other.games;
+        self.sum += other.sum;
+        self.sum_squares += other.sum_squares;
+        self.min = match (self.min, other.min) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.max = self.max.max(other.max);
+        if self.histogram.len() < other.histogram.len() {
+            self.histogram.resize(other.histogram.len(), 0);
+        }
+        for (count, other) in self.histogram.iter_mut().zip(other.histogram) {
+            *count += other;
+        }
+        self.upper_bonuses += other.upper_bonuses;
+        self.yahtzees += other.yahtzees;
+        for (sum, other) in self.categories.iter_mut().zip(other.categories) {
+            *sum += other;
+        }
+    }
+}
+
+/// Plays `config.games` solitaire games with a bot, spread over threads.
+/// Game `i` always gets the same dice, so reports only depend on the seed.
+/// The optimal strategy needs `solver`.
+pub fn run(config: &SimConfig, solver: Option<Arc<Solver>>) -> Report {
+    let threads = config.threads.max(1) as u64;
+    let totals = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|t| {
+                let solver = solver.clone();
+                scope.spawn(move || {
+                    let mut totals = Totals::default();
+                    for i in (t..config.games).step_by(threads as usize) {
+                        let seed = game_seed(config.seed, i);
+                        let mut bot = Bot::new(config.strategy, seed, solver.clone());
+                        let mut roller = SeededRoller::new(seed);
+                        let mut game = Game::new();
+                        while !game.is_game_over() {
+                            bot.play_turn(&mut game, &mut roller)
+                                .expect("bots only make legal moves");
+                        }
+                        totals.add(game.rolls());
+                    }
+                    totals
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .fold(Totals::default(), |mut all, totals| {
+                all.merge(totals);
+                all
+            })
+    });
+    report(config, totals)
+}
+
+/// Spreads game numbers over the seed space so neighbouring seeds share no games.
+pub(crate) fn game_seed(seed: u64, game: u64) -> u64 {
+    seed.wrapping_add(game.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+}
+
+fn report(config: &SimConfig, totals: Totals) -> Report {
+    let games = totals.games.max(1) as f64;
+    let mean = totals.sum as f64 / games;
+    let variance = (totals.sum_squares as f64 / games - mean * mean).max(0.0);
+    Report {
+        strategy: config.strategy,
+        seed: config.seed,
+        games: totals.games,
+        mean,
+        stddev: variance.sqrt(),
+        min: totals.min.unwrap_or(0),
+        max: totals.max,
+        histogram: totals
+            .histogram
+            .iter()
+            .enumerate()
+            .map(|(i, count)| Bucket {
+                from: i as u32 * BUCKET_WIDTH,
+                to: (i as u32 + 1) * BUCKET_WIDTH - 1,
+                count: *count,
+            })
+            .collect(),
+        upper_bonus_rate: totals.upper_bonuses as f64 / games,
+        yahtzee_rate: totals.yahtzees as f64 / games,
+        categories: RollType::STANDARD
+            .iter()
+            .zip(totals.categories)
+            .map(|(category, sum)| CategoryAverage {
+                category: *category,
+                average: sum as f64 / games,
+            })
+            .collect(),
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Strategy     {}", self.strategy)?;
+        writeln!(f, "Games        {} (seed {})", self.games, self.seed)?;
+        writeln!(f, "Mean         {:.2}", self.mean)?;
+        writeln!(f, "Std dev      {:.2}", self.stddev)?;
+        writeln!(f, "Min / max    {} / {}", self.min, self.max)?;
+        writeln!(f, "Upper bonus  {:.1}%", self.upper_bonus_rate * 100.0)?;
+        writeln!(f, "Yahtzee      {:.1}%", self.yahtzee_rate * 100.0)?;
+
+        writeln!(f, "\nScores")?;
+        let most = self.histogram.iter().map(|b| b.count).max().unwrap_or(0).max(1);
+        for bucket in &self.histogram {
+            let bar = "█".repeat((bucket.count * BAR_WIDTH).div_ceil(most) as usize);
+            writeln!(f, "{:>4}-{:<4} {:>7} {bar}", bucket.from, bucket.to, bucket.count)?;
+        }
+
+        writeln!(f, "\nCategory averages")?;
+        for category in &self.categories {
+            let name = format!("{:?}", category.category);
+            writeln!(f, "  {name:<15} {:>6.2}", category.average)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_does_not_depend_on_threads() {
+        let config = |threads| SimConfig {
+            games: 40,
+            strategy: Difficulty::Heuristic,
+            seed: 11,
+            threads,
+        };
+        let report = run(&config(1), None);
+        assert_eq!(report, run(&config(3), None));
+
+        assert_eq!(report.games, 40);
+        assert_eq!(report.histogram.iter().map(|b| b.count).sum::<u64>(), 40);
+        assert!(report.min as f64 <= report.mean && report.mean <= report.max as f64);
+        assert!((0.0..=1.0).contains(&report.upper_bonus_rate));
+        let category_total: f64 = report.categories.iter().map(|c| c.average).sum();
+        let bonus = report.upper_bonus_rate * 35.0;
+        assert!((category_total + bonus - report.mean).abs() < 1e-9);
+    }
+}
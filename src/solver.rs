@@ -0,0 +1,431 @@
+pub mod dice;
+
+use std::{fs, io, path::PathBuf, thread};
+
+use crate::{
+    model::{
+        die::DieFace,
+        roll::{AllRolls, Roll, RollType},
+    },
+    score_util::{calc_score, can_score},
+    storage,
+};
+use dice::{Counts, DiceTables, counts_of, values_of};
+
+const CATEGORY_COUNT: usize = RollType::STANDARD.len();
+const UPPER_COUNT: usize = 6;
+const YAHTZEE: usize = CATEGORY_COUNT - 1;
+const UPPER_BONUS_THRESHOLD: u8 = 63;
+const UPPER_BONUS: f32 = 35.0;
+const YAHTZEE_BONUS: f32 = 100.0;
+const ALL_OPEN: u16 = (1 << CATEGORY_COUNT) - 1;
+const STATE_COUNT: usize = (1 << CATEGORY_COUNT) * (UPPER_BONUS_THRESHOLD as usize + 1) * 2;
+const CACHE_MAGIC: &[u8; 8] = b"RTZEV001";
+
+/// The part of a solitaire scorecard that matters for the rest of the game.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SolverState {
+    /// Bit per open category, in `RollType::STANDARD` order.
+    pub open: u16,
+    /// Upper section total, capped at the bonus threshold.
+    pub upper: u8,
+    /// Whether the Yahtzee box holds 50, so further Yahtzees earn a bonus.
+    pub yahtzee_bonus: bool,
+}
+
+impl SolverState {
+    pub fn start() -> SolverState {
+        SolverState {
+            open: ALL_OPEN,
+            upper: 0,
+            yahtzee_bonus: false,
+        }
+    }
+
+    pub fn from_rolls(rolls: &AllRolls) -> SolverState {
+        let open = RollType::STANDARD
+            .iter()
+            .enumerate()
+            .filter(|(_, roll_type)| rolls.roll(**roll_type).score.is_none())
+            .fold(0, |open, (i, _)| open | 1 << i);
+        SolverState {
+            open,
+            upper: rolls.bonus_status().0.min(UPPER_BONUS_THRESHOLD.into()) as u8,
+            yahtzee_bonus: rolls.roll(RollType::Yahtzee).score.is_some_and(|s| s >= 50),
+        }
+    }
+
+    fn is_open(self, category: usize) -> bool {
+        self.open & 1 << category != 0
+    }
+
+    fn index(self) -> usize {
+        ((self.open as usize * (UPPER_BONUS_THRESHOLD as usize + 1)) + self.upper as usize) * 2
+            + self.yahtzee_bonus as usize
+    }
+
+    /// The state after scoring `score` in `category`, and any upper bonus it earns.
+    fn after(self, category: usize, score: u32) -> (SolverState, f32) {
+        let mut next = self;
+        next.open &= !(1 << category);
+        let mut reward = 0.0;
+        if category < UPPER_COUNT {
+            let upper = (self.upper as u32 + score).min(UPPER_BONUS_THRESHOLD.into()) as u8;
+            if self.upper < UPPER_BONUS_THRESHOLD && upper == UPPER_BONUS_THRESHOLD {
+                reward = UPPER_BONUS;
+            }
+            next.upper = upper;
+        }
+        if category == YAHTZEE {
+            next.yahtzee_bonus = score >= 50;
+        }
+        (next, reward)
+    }
+
+    /// A scorecard with the same categories filled, for checking the Joker rules.
+    fn scorecard(self) -> AllRolls {
+        let mut rolls = AllRolls::new();
+        for (i, roll_type) in RollType::STANDARD.iter().enumerate() {
+            if !self.is_open(i) {
+                rolls.roll_mut(*roll_type).score = Some(0);
+            }
+        }
+        if self.yahtzee_bonus {
+            rolls.roll_mut(RollType::Yahtzee).score = Some(50);
+        }
+        rolls
+    }
+}
+
+/// Optimal strategy for solitaire Yahtzee under the standard rules, including
+/// the upper bonus, Yahtzee bonuses and forced Joker rules.
+///
+/// Holds the expected final score from the start of a turn in every state.
+pub struct Solver {
+    tables: DiceTables,
+    /// Score of each roll in each category.
+    scores: Vec<[u32; CATEGORY_COUNT]>,
+    /// Score of each roll in each category when it is played as a Joker.
+    joker_scores: Vec<[u32; CATEGORY_COUNT]>,
+    values: Vec<f32>,
+}
+
+impl Solver {
+    /// Loads the cached table, or computes it and caches it for next time.
+    pub fn load_or_build() -> Solver {
+        if let Some(solver) = Solver::load() {
+            return solver;
+        }
+        let solver = Solver::build();
+        // a missing cache only costs the time to build it again
+        let _ = solver.store();
+        solver
+    }
+
+    /// Computes the whole table, which takes a while in debug builds.
+    pub fn build() -> Solver {
+        Solver::build_levels(CATEGORY_COUNT as u32)
+    }
+
+    pub fn load() -> Option<Solver> {
+        let bytes = fs::read(cache_path()?).ok()?;
+        let data = bytes.strip_prefix(CACHE_MAGIC)?;
+        if data.len() != STATE_COUNT * 4 {
+            return None;
+        }
+        let mut solver = Solver::empty();
+        solver.values = data
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        Some(solver)
+    }
+
+    pub fn store(&self) -> io::Result<()> {
+        let Some(path) = cache_path() else {
+            return Ok(());
+        };
+        let mut bytes = CACHE_MAGIC.to_vec();
+        bytes.extend(self.values.iter().flat_map(|v| v.to_le_bytes()));
+        storage::write_atomic(&path, &bytes)
+    }
+
+    /// Expected final score still to come from the start of a turn in `state`.
+    pub fn expected_value(&self, state: SolverState) -> f32 {
+        self.values[state.index()]
+    }
+
+    /// Every category `dice` may be scored in, with the expected final score
+    /// still to come (this turn's score included) after scoring it.
+    pub fn category_values(&self, state: SolverState, dice: &[u8]) -> Vec<(RollType, f32)> {
+        let roll = self.tables.roll_index(&counts_of(dice.iter().copied()));
+        let joker_card = (!state.is_open(YAHTZEE)).then(|| state.scorecard());
+        self.category_options(state, roll, joker_card.as_ref())
+            .map(|(category, value)| (RollType::STANDARD[category], value))
+            .collect()
+    }
+
+    pub fn best_category(&self, state: SolverState, dice: &[u8]) -> Option<(RollType, f32)> {
+        self.category_values(state, dice)
+            .into_iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Expected final score still to come when keeping the `held` dice with
+    /// `rolls_left` rolls to go. Holding every die means scoring this roll.
+    pub fn hold_value(&self, state: SolverState, dice: &[u8], held: &[bool], rolls_left: u8) -> f32 {
+        let keeps = self.keep_table(state, rolls_left);
+        keeps[self.keep_for(dice, held)]
+    }
+
+    /// The dice to hold for the best expected final score, and that score.
+    pub fn best_hold(&self, state: SolverState, dice: &[u8], rolls_left: u8) -> (Vec<bool>, f32) {
+        let keeps = self.keep_table(state, rolls_left);
+        (0..1u32 << dice.len())
+            .map(|mask| {
+                let held: Vec<bool> = (0..dice.len()).map(|i| mask & 1 << i != 0).collect();
+                let value = keeps[self.keep_for(dice, &held)];
+                (held, value)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap()
+    }
+
+    fn empty() -> Solver {
+        let tables = DiceTables::new();
+        let card = AllRolls::new();
+        let mut joker_card = AllRolls::new();
+        joker_card.roll_mut(RollType::Yahtzee).score = Some(0);
+
+        let score_rolls = |rolls: &AllRolls| -> Vec<[u32; CATEGORY_COUNT]> {
+            tables
+                .rolls
+                .iter()
+                .map(|counts| {
+                    let faces = faces_of(counts);
+                    RollType::STANDARD.map(|roll_type| calc_score(Roll::new(roll_type), rolls, &faces))
+                })
+                .collect()
+        };
+
+        Solver {
+            scores: score_rolls(&card),
+            joker_scores: score_rolls(&joker_card),
+            tables,
+            values: vec![0.0; STATE_COUNT],
+        }
+    }
+
+    /// Fills in every state with at most `max_open` open categories, working
+    /// up from the end of the game since each turn only leads to states with
+    /// one category fewer.
+    pub(crate) fn build_levels(max_open: u32) -> Solver {
+        let mut solver = Solver::empty();
+        let reachable = reachable_upper_totals();
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+
+        let reachable = &reachable;
+
+        for level in 1..=max_open {
+            let states: Vec<SolverState> = (0..=ALL_OPEN)
+                .filter(|open| open.count_ones() == level)
+                .flat_map(|open| {
+                    let filled_upper = !open as usize & ((1 << UPPER_COUNT) - 1);
+                    let yahtzee_filled = open & 1 << YAHTZEE == 0;
+                    (0..=UPPER_BONUS_THRESHOLD)
+                        .filter(move |upper| reachable[filled_upper][*upper as usize])
+                        .flat_map(move |upper| {
+                            [false, true]
+                                .into_iter()
+                                .filter(move |bonus| yahtzee_filled || !bonus)
+                                .map(move |yahtzee_bonus| SolverState { open, upper, yahtzee_bonus })
+                        })
+                })
+                .collect();
+
+            let chunk_size = states.len().div_ceil(threads).max(1);
+            let results: Vec<Vec<(usize, f32)>> = thread::scope(|scope| {
+                let solver = &solver;
+                let handles: Vec<_> = states
+                    .chunks(chunk_size)
+                    .map(|chunk| {
+                        scope.spawn(move || {
+                            chunk
+                                .iter()
+                                .map(|state| (state.index(), solver.turn_value(*state)))
+                                .collect()
+                        })
+                    })
+                    .collect();
+                handles.into_iter().map(|h| h.join().unwrap()).collect()
+            });
+            for (index, value) in results.into_iter().flatten() {
+                solver.values[index] = value;
+            }
+        }
+        solver
+    }
+
+    /// Expected score from the start of a turn, playing optimally.
+    fn turn_value(&self, state: SolverState) -> f32 {
+        let first_roll = self.best_keeps(&self.keep_table(state, 2));
+        self.tables.keep_outcomes[self.tables.empty_keep()]
+            .iter()
+            .map(|(roll, p)| p * first_roll[*roll])
+            .sum()
+    }
+
+    /// Value of each keep with `rolls_left` rolls to go.
+    fn keep_table(&self, state: SolverState, rolls_left: u8) -> Vec<f32> {
+        let mut keeps = self.keep_values(&self.final_values(state));
+        for _ in 1..rolls_left {
+            keeps = self.keep_values(&self.best_keeps(&keeps));
+        }
+        keeps
+    }
+
+    /// Value of each roll once there are no rolls left.
+    fn final_values(&self, state: SolverState) -> Vec<f32> {
+        let joker_card = (!state.is_open(YAHTZEE)).then(|| state.scorecard());
+        (0..self.tables.rolls.len())
+            .map(|roll| {
+                self.category_options(state, roll, joker_card.as_ref())
+                    .map(|(_, value)| value)
+                    .fold(f32::NEG_INFINITY, f32::max)
+            })
+            .collect()
+    }
+
+    /// The allowed categories for `roll` and the value of scoring each.
+    fn category_options<'a>(
+        &'a self,
+        state: SolverState,
+        roll: usize,
+        joker_card: Option<&'a AllRolls>,
+    ) -> impl Iterator<Item = (usize, f32)> + 'a {
+        let counts = &self.tables.rolls[roll];
+        let joker = joker_card.filter(|_| counts.contains(&5));
+        let faces = joker.map(|_| faces_of(counts));
+        let bonus = if joker.is_some() && state.yahtzee_bonus { YAHTZEE_BONUS } else { 0.0 };
+
+        (0..CATEGORY_COUNT)
+            .filter(move |category| state.is_open(*category))
+            .filter(move |category| match (joker, &faces) {
+                (Some(card), Some(faces)) => can_score(RollType::STANDARD[*category], card, faces),
+                _ => true,
+            })
+            .map(move |category| {
+                let score = match joker {
+                    Some(_) => self.joker_scores[roll][category],
+                    None => self.scores[roll][category],
+                };
+                let (next, reward) = state.after(category, score);
+                (category, score as f32 + reward + bonus + self.expected_value(next))
+            })
+    }
+
+    fn keep_values(&self, roll_values: &[f32]) -> Vec<f32> {
+        self.tables
+            .keep_outcomes
+            .iter()
+            .map(|outcomes| outcomes.iter().map(|(roll, p)| p * roll_values[*roll]).sum())
+            .collect()
+    }
+
+    fn best_keeps(&self, keep_values: &[f32]) -> Vec<f32> {
+        self.tables
+            .roll_keeps
+            .iter()
+            .map(|keeps| keeps.iter().map(|k| keep_values[*k]).fold(f32::NEG_INFINITY, f32::max))
+            .collect()
+    }
+
+    fn keep_for(&self, dice: &[u8], held: &[bool]) -> usize {
+        let kept = dice.iter().zip(held).filter(|(_, h)| **h).map(|(d, _)| *d);
+        self.tables.keep_index(&counts_of(kept))
+    }
+}
+
+fn faces_of(counts: &Counts) -> Vec<DieFace> {
+    values_of(counts).into_iter().map(DieFace::new).collect()
+}
+
+/// For each set of filled upper categories, which capped upper totals can occur.
+fn reachable_upper_totals() -> Vec<[bool; UPPER_BONUS_THRESHOLD as usize + 1]> {
+    (0..1usize << UPPER_COUNT)
+        .map(|filled| {
+            let mut reachable = [false; UPPER_BONUS_THRESHOLD as usize + 1];
+            reachable[0] = true;
+            for category in (0..UPPER_COUNT).filter(|c| filled & 1 << c != 0) {
+                let face = category + 1;
+                let mut next = [false; UPPER_BONUS_THRESHOLD as usize + 1];
+                for total in (0..reachable.len()).filter(|t| reachable[*t]) {
+                    for count in 0..=5 {
+                        next[(total + face * count).min(UPPER_BONUS_THRESHOLD as usize)] = true;
+                    }
+                }
+                reachable = next;
+            }
+            reachable
+        })
+        .collect()
+}
+
+fn cache_path() -> Option<PathBuf> {
+    storage::data_dir().map(|dir| dir.join("solver-table.bin"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn only_open(roll_type: RollType, upper: u8) -> SolverState {
+        let category = RollType::STANDARD.iter().position(|r| *r == roll_type).unwrap();
+        SolverState {
+            open: 1 << category,
+            upper,
+            yahtzee_bonus: false,
+        }
+    }
+
+    #[test]
+    fn test_last_turn_values() {
+        let solver = Solver::build_levels(1);
+
+        // known values for the final turn: optimal chance and yahtzee chasing
+        let chance = solver.expected_value(only_open(RollType::Chance, 0));
+        assert!((chance - 23.33).abs() < 0.01, "chance was {chance}");
+        let yahtzee = solver.expected_value(only_open(RollType::Yahtzee, 0));
+        assert!((yahtzee - 50.0 * 0.04603).abs() < 0.01, "yahtzee was {yahtzee}");
+
+        // one six short of the upper bonus makes the bonus part of the value
+        let sixes = solver.expected_value(only_open(RollType::Sixes, 57));
+        assert!(sixes > 35.0);
+    }
+
+    #[test]
+    fn test_best_hold_and_category() {
+        let solver = Solver::build_levels(1);
+        let state = only_open(RollType::Chance, 0);
+        let (held, _) = solver.best_hold(state, &[6, 6, 5, 1, 2], 1);
+        assert_eq!(held, vec![true, true, true, false, false]);
+
+        let fives = only_open(RollType::Fives, 0);
+        let (category, value) = solver.best_category(fives, &[5, 5, 5, 1, 2]).unwrap();
+        assert_eq!(category, RollType::Fives);
+        assert_eq!(value, 15.0);
+    }
+
+    #[test]
+    fn test_state_from_rolls() {
+        let mut rolls = AllRolls::new();
+        rolls.roll_mut(RollType::Sixes).score = Some(24);
+        rolls.roll_mut(RollType::Yahtzee).score = Some(150);
+        let state = SolverState::from_rolls(&rolls);
+        assert_eq!(state.upper, 24);
+        assert!(state.yahtzee_bonus);
+        assert!(!state.is_open(5));
+        assert!(!state.is_open(YAHTZEE));
+        assert!(state.is_open(0));
+    }
+}
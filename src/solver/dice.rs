@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use crate::model::ruleset::Ruleset;
+
+/// The strategy table only covers the standard rules.
+const DICE_COUNT: usize = Ruleset::Standard.dice_count();
+
+/// How many of each face, from ones to sixes.
+pub type Counts = [u8; 6];
+
+/// Every distinct set of dice that can be rolled or kept, with the odds of
+/// going from a kept set to each roll.
+pub struct DiceTables {
+    /// Every roll of all five dice.
+    pub rolls: Vec<Counts>,
+    /// Every set of zero to five dice that can be kept.
+    pub keeps: Vec<Counts>,
+    /// For each keep, the rolls it can lead to and their probabilities.
+    pub keep_outcomes: Vec<Vec<(usize, f32)>>,
+    /// For each roll, the keeps that can be taken from it.
+    pub roll_keeps: Vec<Vec<usize>>,
+    roll_index: HashMap<Counts, usize>,
+    keep_index: HashMap<Counts, usize>,
+}
+
+impl DiceTables {
+    pub fn new() -> DiceTables {
+        let rolls = multisets(DICE_COUNT as u8);
+        let keeps: Vec<Counts> = (0..=DICE_COUNT as u8).flat_map(multisets).collect();
+        let roll_index: HashMap<Counts, usize> =
+            rolls.iter().enumerate().map(|(i, c)| (*c, i)).collect();
+        let keep_index: HashMap<Counts, usize> =
+            keeps.iter().enumerate().map(|(i, c)| (*c, i)).collect();
+
+        let keep_outcomes = keeps
+            .iter()
+            .map(|keep| {
+                let rerolled = DICE_COUNT as u8 - size(keep);
+                multisets(rerolled)
+                    .iter()
+                    .map(|added| {
+                        let roll: Counts = std::array::from_fn(|f| keep[f] + added[f]);
+                        (roll_index[&roll], probability(added))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let roll_keeps = rolls
+            .iter()
+            .map(|roll| sub_multisets(roll).iter().map(|k| keep_index[k]).collect())
+            .collect();
+
+        DiceTables {
+            rolls,
+            keeps,
+            keep_outcomes,
+            roll_keeps,
+            roll_index,
+            keep_index,
+        }
+    }
+
+    pub fn roll_index(&self, counts: &Counts) -> usize {
+        self.roll_index[counts]
+    }
+
+    pub fn keep_index(&self, counts: &Counts) -> usize {
+        self.keep_index[counts]
+    }
+
+    /// Index of the empty keep, whose outcomes are the odds of a fresh roll.
+    pub fn empty_keep(&self) -> usize {
+        self.keep_index[&[0; 6]]
+    }
+}
+
+impl Default for DiceTables {
+    fn default() -> Self {
+        DiceTables::new()
+    }
+}
+
+pub fn counts_of(values: impl IntoIterator<Item = u8>) -> Counts {
+    let mut counts = [0; 6];
+    for value in values {
+        counts[value as usize - 1] += 1;
+    }
+    counts
+}
+
+/// The dice in `counts`, lowest first.
+pub fn values_of(counts: &Counts) -> Vec<u8> {
+    (1..=6u8)
+        .flat_map(|face| std::iter::repeat_n(face, counts[face as usize - 1] as usize))
+        .collect()
+}
+
+fn size(counts: &Counts) -> u8 {
+    counts.iter().sum()
+}
+
+/// Every way `n` dice can land, ignoring order.
+fn multisets(n: u8) -> Vec<Counts> {
+    fn fill(face: usize, left: u8, current: &mut Counts, out: &mut Vec<Counts>) {
+        if face == 5 {
+            current[5] = left;
+            out.push(*current);
+            return;
+        }
+        for count in 0..=left {
+            current[face] = count;
+            fill(face + 1, left - count, current, out);
+        }
+    }
+    let mut out = Vec::new();
+    fill(0, n, &mut [0; 6], &mut out);
+    out
+}
+
+fn sub_multisets(counts: &Counts) -> Vec<Counts> {
+    let mut out = vec![[0; 6]];
+    for face in 0..6 {
+        out = out
+            .into_iter()
+            .flat_map(|sub| {
+                (0..=counts[face]).map(move |count| {
+                    let mut next = sub;
+                    next[face] = count;
+                    next
+                })
+            })
+            .collect();
+    }
+    out
+}
+
+/// Odds of rolling exactly `counts` with that many dice.
+fn probability(counts: &Counts) -> f32 {
+    let factorial = |n: u8| (1..=n as u32).product::<u32>() as f32;
+    let n = size(counts);
+    let arrangements = factorial(n) / counts.iter().map(|c| factorial(*c)).product::<f32>();
+    arrangements / 6f32.powi(n as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_sizes_and_odds() {
+        let tables = DiceTables::new();
+        assert_eq!(tables.rolls.len(), 252);
+        assert_eq!(tables.keeps.len(), 462);
+        for outcomes in &tables.keep_outcomes {
+            let total: f32 = outcomes.iter().map(|(_, p)| p).sum();
+            assert!((total - 1.0).abs() < 1e-5);
+        }
+        let yahtzee = tables.roll_index(&counts_of([6; 5]));
+        assert_eq!(tables.roll_keeps[yahtzee].len(), 6);
+    }
+}
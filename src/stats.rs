@@ -0,0 +1,233 @@
+use std::{fs, io, path::PathBuf};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::mode::Mode,
+    high_scores::Variant,
+    model::{house_rules::HouseRules, player::Player, roll::RollType, ruleset::Ruleset},
+    storage,
+};
+
+const STATS_VERSION: u32 = 1;
+
+/// A scorecard box as it was filled in.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Scored {
+    pub category: RollType,
+    pub points: u32,
+}
+
+/// One player's finished game.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameRecord {
+    pub mode: Mode,
+    pub ruleset: Ruleset,
+    #[serde(default)]
+    pub house_rules: HouseRules,
+    pub name: String,
+    pub date: NaiveDate,
+    pub total: u32,
+    pub upper_bonus: u32,
+    pub yahtzee_bonus_count: u32,
+    /// Every box of every column.
+    pub boxes: Vec<Scored>,
+}
+
+impl GameRecord {
+    /// The record of a player's finished scorecard, dated today.
+    pub fn new(player: &Player, variant: Variant) -> GameRecord {
+        GameRecord {
+            mode: variant.mode,
+            ruleset: variant.ruleset,
+            house_rules: variant.house_rules,
+            name: player.name.clone(),
+            date: chrono::Local::now().date_naive(),
+            total: player.total_score(),
+            upper_bonus: player.upper_bonus(),
+            yahtzee_bonus_count: player.yahtzee_bonus_count(),
+            boxes: player
+                .columns
+                .iter()
+                .flat_map(|rolls| rolls.iter())
+                .map(|r| Scored {
+                    category: r.roll_type,
+                    points: r.score.unwrap_or(0),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn variant(&self) -> Variant {
+        Variant {
+            mode: self.mode,
+            ruleset: self.ruleset,
+            house_rules: self.house_rules,
+        }
+    }
+
+    fn has_yahtzee(&self) -> bool {
+        self.boxes.iter().any(|b| b.category == RollType::Yahtzee && b.points > 0)
+    }
+}
+
+/// How a category has gone over all games.
+#[derive(Debug, PartialEq)]
+pub struct CategoryStats {
+    pub category: RollType,
+    pub average: f64,
+    /// Share of boxes scored 0.
+    pub scratch_rate: f64,
+}
+
+/// The lifetime statistics of one variant.
+#[derive(Debug, PartialEq)]
+pub struct Summary {
+    pub games: usize,
+    pub mean: f64,
+    pub median: f64,
+    pub best: u32,
+    /// Share of games that earned the upper section bonus.
+    pub upper_bonus_rate: f64,
+    /// Share of games with a Yahtzee.
+    pub yahtzee_rate: f64,
+    /// Share of games with a bonus Yahtzee.
+    pub bonus_yahtzee_rate: f64,
+    /// In scorecard order.
+    pub categories: Vec<CategoryStats>,
+    /// Every total, oldest first.
+    pub totals: Vec<u32>,
+}
+
+/// Every finished game played at this terminal.
+#[derive(Serialize, Deserialize)]
+pub struct Stats {
+    version: u32,
+    pub games: Vec<GameRecord>,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Stats::new()
+    }
+}
+
+impl Stats {
+    pub fn new() -> Stats {
+        Stats {
+            version: STATS_VERSION,
+            games: Vec::new(),
+        }
+    }
+
+    /// Loads the games from disk, starting afresh if there are none.
+    pub fn load() -> Stats {
+        stats_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str::<Stats>(&contents).ok())
+            .filter(|stats| stats.version == STATS_VERSION)
+            .unwrap_or_default()
+    }
+
+    pub fn store(&self) -> io::Result<()> {
+        let Some(path) = stats_path() else {
+            return Ok(());
+        };
+        let json = serde_json::to_vec(self).map_err(io::Error::other)?;
+        storage::write_atomic(&path, &json)
+    }
+
+    /// The statistics of the games of `variant`, if any were played.
+    pub fn summary(&self, variant: Variant) -> Option<Summary> {
+        let games: Vec<&GameRecord> = self.games.iter().filter(|g| g.variant() == variant).collect();
+        if games.is_empty() {
+            return None;
+        }
+        let count = games.len() as f64;
+        let share = |f: &dyn Fn(&GameRecord) -> bool| games.iter().filter(|g| f(g)).count() as f64 / count;
+        let totals: Vec<u32> = games.iter().map(|g| g.total).collect();
+        let mut sorted = totals.clone();
+        sorted.sort_unstable();
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            f64::from(sorted[middle - 1] + sorted[middle]) / 2.0
+        } else {
+            f64::from(sorted[middle])
+        };
+        let categories = variant
+            .ruleset
+            .categories()
+            .iter()
+            .map(|category| {
+                let boxes: Vec<u32> = games
+                    .iter()
+                    .flat_map(|g| g.boxes.iter())
+                    .filter(|b| b.category == *category)
+                    .map(|b| b.points)
+                    .collect();
+                let n = boxes.len().max(1) as f64;
+                CategoryStats {
+                    category: *category,
+                    average: boxes.iter().map(|p| f64::from(*p)).sum::<f64>() / n,
+                    scratch_rate: boxes.iter().filter(|p| **p == 0).count() as f64 / n,
+                }
+            })
+            .collect();
+        Some(Summary {
+            games: games.len(),
+            mean: totals.iter().map(|t| f64::from(*t)).sum::<f64>() / count,
+            median,
+            best: sorted[sorted.len() - 1],
+            upper_bonus_rate: share(&|g| g.upper_bonus > 0),
+            yahtzee_rate: share(&GameRecord::has_yahtzee),
+            bonus_yahtzee_rate: share(&|g| g.yahtzee_bonus_count > 0),
+            categories,
+            totals,
+        })
+    }
+}
+
+fn stats_path() -> Option<PathBuf> {
+    storage::data_dir().map(|dir| dir.join("stats.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(scores: [u32; 13]) -> GameRecord {
+        let mut player = Player::new("a");
+        for (roll_type, score) in RollType::STANDARD.into_iter().zip(scores) {
+            player.columns[0].roll_mut(roll_type).score = Some(score);
+        }
+        GameRecord::new(&player, Variant::default())
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut stats = Stats::new();
+        assert_eq!(stats.summary(Variant::default()), None);
+        stats.games.push(game([3, 6, 9, 12, 15, 18, 20, 0, 25, 30, 40, 22, 150]));
+        stats.games.push(game([1, 2, 3, 4, 5, 6, 10, 0, 0, 30, 0, 15, 0]));
+        stats.games.push(game([2, 4, 6, 8, 10, 12, 15, 0, 25, 30, 40, 20, 0]));
+        let summary = stats.summary(Variant::default()).unwrap();
+        assert_eq!(summary.games, 3);
+        assert_eq!(summary.totals, vec![385, 76, 172]);
+        assert_eq!(summary.median, 172.0);
+        assert_eq!(summary.best, 385);
+        assert!((summary.upper_bonus_rate - 1.0 / 3.0).abs() < 1e-9);
+        assert!((summary.yahtzee_rate - 1.0 / 3.0).abs() < 1e-9);
+        assert!((summary.bonus_yahtzee_rate - 1.0 / 3.0).abs() < 1e-9);
+        let four_of_a_kind = &summary.categories[7];
+        assert_eq!(four_of_a_kind.category, RollType::FourOfAKind);
+        assert_eq!(four_of_a_kind.scratch_rate, 1.0);
+        assert_eq!(summary.categories[8].average, 50.0 / 3.0);
+
+        let triple = Variant {
+            mode: Mode::Triple,
+            ..Variant::default()
+        };
+        assert_eq!(stats.summary(triple), None);
+    }
+}
@@ -0,0 +1,573 @@
+use std::{collections::HashSet, fmt, str::FromStr, sync::Arc, thread};
+
+use serde::Serialize;
+
+use crate::{
+    bot::{Bot, Difficulty, external::{self, ExternalBot}},
+    game::{Game, roller::SeededRoller},
+    model::player::Player,
+    sim::game_seed,
+    solver::Solver,
+};
+
+/// Normal quantile of the 95% confidence intervals.
+const Z: f64 = 1.96;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Format {
+    /// Everyone plays everyone once.
+    RoundRobin,
+    /// Each round pairs entrants with similar points who have not met yet.
+    Swiss,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Format, String> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "round-robin" => Ok(Format::RoundRobin),
+            "swiss" => Ok(Format::Swiss),
+            _ => Err(format!("unknown format: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Format::RoundRobin => "Round robin",
+            Format::Swiss => "Swiss",
+        })
+    }
+}
+
+/// Who makes an entrant's moves.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Strategy {
+    Bot(Difficulty),
+    /// A shell command playing over stdin and stdout, see `bot::external`.
+    Program(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Entrant {
+    pub name: String,
+    pub strategy: Strategy,
+}
+
+impl Entrant {
+    pub fn bot(difficulty: Difficulty) -> Entrant {
+        Entrant {
+            name: difficulty.to_string(),
+            strategy: Strategy::Bot(difficulty),
+        }
+    }
+
+    pub fn program(command: impl Into<String>) -> Entrant {
+        let command = command.into();
+        Entrant {
+            name: external::program_name(&command),
+            strategy: Strategy::Program(command),
+        }
+    }
+}
+
+pub struct TournamentConfig {
+    pub entrants: Vec<Entrant>,
+    pub format: Format,
+    /// Swiss rounds to play, ignored in round robins.
+    pub rounds: usize,
+    /// Games in each match.
+    pub games: u64,
+    /// Points for sitting out a Swiss round, usually `games`, as much as
+    /// winning the match.
+    pub bye_points: f64,
+    pub seed: u64,
+    pub threads: usize,
+}
+
+impl TournamentConfig {
+    /// Enough Swiss rounds to single out a winner among `entrants`.
+    pub fn default_rounds(entrants: usize) -> usize {
+        entrants.next_power_of_two().trailing_zeros().max(1) as usize
+    }
+
+    pub fn needs_solver(&self) -> bool {
+        self.entrants
+            .iter()
+            .any(|e| matches!(e.strategy, Strategy::Bot(d) if d.needs_solver()))
+    }
+}
+
+/// What one entrant made of the tournament.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Standing {
+    pub rank: usize,
+    /// Where the entrant is in the field, which tells entrants apart even
+    /// if they share a name.
+    pub entrant: usize,
+    pub name: String,
+    pub strategy: Strategy,
+    /// A point per game won and half a point per draw, with the bye points
+    /// for each Swiss bye.
+    pub points: f64,
+    pub games: u64,
+    pub wins: u64,
+    pub draws: u64,
+    pub losses: u64,
+    pub forfeits: u64,
+    pub byes: u64,
+    /// Share of games won, draws counting half.
+    pub win_rate: f64,
+    pub win_rate_interval: [f64; 2],
+    pub mean: f64,
+    pub mean_interval: [f64; 2],
+}
+
+/// How one entrant fared against another, both given by where they are in
+/// the field as in `Standing::entrant`.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct HeadToHead {
+    pub entrant: usize,
+    pub opponent: usize,
+    pub games: u64,
+    pub wins: u64,
+    pub draws: u64,
+    pub losses: u64,
+    pub win_rate: f64,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Leaderboard {
+    pub format: Format,
+    pub rounds: usize,
+    pub games_per_match: u64,
+    pub bye_points: f64,
+    pub seed: u64,
+    pub standings: Vec<Standing>,
+    pub head_to_head: Vec<HeadToHead>,
+}
+
+/// One entrant's side of a game.
+#[derive(Clone, Copy, Debug)]
+struct Side {
+    score: u32,
+    forfeited: bool,
+}
+
+#[derive(Default)]
+struct Record {
+    scores: Vec<u32>,
+    wins: u64,
+    draws: u64,
+    losses: u64,
+    forfeits: u64,
+    byes: u64,
+}
+
+impl Record {
+    fn points(&self, bye_points: f64) -> f64 {
+        self.wins as f64 + self.draws as f64 / 2.0 + self.byes as f64 * bye_points
+    }
+}
+
+/// Wins, draws and losses of each entrant against each other one.
+struct Results {
+    records: Vec<Record>,
+    /// `[wins, draws, losses]` of the row entrant against the column one.
+    pairs: Vec<Vec<[u64; 3]>>,
+    met: HashSet<(usize, usize)>,
+}
+
+impl Results {
+    fn new(entrants: usize) -> Results {
+        Results {
+            records: (0..entrants).map(|_| Record::default()).collect(),
+            pairs: vec![vec![[0; 3]; entrants]; entrants],
+            met: HashSet::new(),
+        }
+    }
+
+    fn add(&mut self, a: usize, b: usize, sides: [Side; 2]) {
+        // forfeiting loses whatever the score, as in the game's standings
+        let key = |side: Side| (!side.forfeited, side.score);
+        let outcome = match key(sides[0]).cmp(&key(sides[1])) {
+            std::cmp::Ordering::Greater => 0,
+            std::cmp::Ordering::Equal => 1,
+            std::cmp::Ordering::Less => 2,
+        };
+        for (entrant, opponent, side, outcome) in [(a, b, sides[0], outcome), (b, a, sides[1], 2 - outcome)] {
+            let record = &mut self.records[entrant];
+            record.scores.push(side.score);
+            record.forfeits += u64::from(side.forfeited);
+            match outcome {
+                0 => record.wins += 1,
+                1 => record.draws += 1,
+                _ => record.losses += 1,
+            }
+            self.pairs[entrant][opponent][outcome] += 1;
+        }
+        self.met.insert((a.min(b), a.max(b)));
+    }
+
+    /// Entrants by points, then by mean score.
+    fn order(&self, bye_points: f64) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.records.len()).collect();
+        order.sort_by(|a, b| {
+            let (a, b) = (&self.records[*a], &self.records[*b]);
+            b.points(bye_points)
+                .total_cmp(&a.points(bye_points))
+                .then(mean(&b.scores).total_cmp(&mean(&a.scores)))
+        });
+        order
+    }
+
+    /// The next Swiss round: neighbours in the standings meet unless they
+    /// already have, and with an odd number the lowest entrant without a bye
+    /// sits out.
+    fn swiss_pairs(&mut self, bye_points: f64) -> Vec<(usize, usize)> {
+        let mut unpaired = self.order(bye_points);
+        if unpaired.len() % 2 == 1 {
+            let bye = unpaired
+                .iter()
+                .rposition(|e| self.records[*e].byes == 0)
+                .unwrap_or(unpaired.len() - 1);
+            let entrant = unpaired.remove(bye);
+            self.records[entrant].byes += 1;
+        }
+        let mut pairs = Vec::new();
+        while !unpaired.is_empty() {
+            let a = unpaired.remove(0);
+            let b = unpaired
+                .iter()
+                .position(|b| !self.met.contains(&(a.min(*b), a.max(*b))))
+                .unwrap_or(0);
+            pairs.push((a, unpaired.remove(b)));
+        }
+        pairs
+    }
+}
+
+/// Plays the tournament. Game `i` of every match is rolled from the same
+/// seed, and each side rolls from its own copy of it, so results only
+/// depend on the seed and entrants are compared on the same dice. The
+/// optimal bot needs `solver`.
+pub fn run(config: &TournamentConfig, solver: Option<Arc<Solver>>) -> Leaderboard {
+    let entrants = config.entrants.len();
+    let mut results = Results::new(entrants);
+    let rounds = match config.format {
+        Format::RoundRobin => {
+            for a in 0..entrants {
+                for b in a + 1..entrants {
+                    play_match(config, &mut results, a, b, solver.as_ref());
+                }
+            }
+            entrants.saturating_sub(1)
+        }
+        Format::Swiss => {
+            for _ in 0..config.rounds {
+                for (a, b) in results.swiss_pairs(config.bye_points) {
+                    play_match(config, &mut results, a, b, solver.as_ref());
+                }
+            }
+            config.rounds
+        }
+    };
+    leaderboard(config, rounds, &results)
+}
+
+fn play_match(config: &TournamentConfig, results: &mut Results, a: usize, b: usize, solver: Option<&Arc<Solver>>) {
+    let threads = config.threads.max(1) as u64;
+    let pair = [&config.entrants[a], &config.entrants[b]];
+    let mut games: Vec<(u64, [Side; 2])> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|t| {
+                scope.spawn(move || {
+                    (t..config.games)
+                        .step_by(threads as usize)
+                        .map(|i| (i, play_game(pair, i, game_seed(config.seed, i), solver)))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles.into_iter().flat_map(|h| h.join().unwrap()).collect()
+    });
+    games.sort_by_key(|(i, _)| *i);
+    for (_, sides) in games {
+        results.add(a, b, sides);
+    }
+}
+
+/// A seat's mover for one game.
+enum Mover {
+    Bot(Box<Bot>),
+    Program(ExternalBot),
+}
+
+/// Plays game `index` of a match, the entrants taking turns to go first.
+fn play_game(pair: [&Entrant; 2], index: u64, seed: u64, solver: Option<&Arc<Solver>>) -> [Side; 2] {
+    let seats = if index.is_multiple_of(2) { [0, 1] } else { [1, 0] };
+    let players = seats.iter().map(|s| Player::new(&pair[*s].name)).collect();
+    let mut game = Game::with_players(players);
+    let mut rollers = [SeededRoller::new(seed), SeededRoller::new(seed)];
+    let mut movers: Vec<Result<Mover, String>> = seats
+        .iter()
+        .map(|s| match &pair[*s].strategy {
+            Strategy::Bot(difficulty) => Ok(Mover::Bot(Box::new(Bot::new(*difficulty, seed, solver.cloned())))),
+            Strategy::Program(command) => ExternalBot::spawn(command)
+                .map(Mover::Program)
+                .map_err(|err| format!("cannot start: {err}")),
+        })
+        .collect();
+    while !game.is_game_over() {
+        let seat = game.current_player_index();
+        let roller = &mut rollers[seat];
+        let turn = match &mut movers[seat] {
+            Ok(Mover::Bot(bot)) => {
+                bot.play_turn(&mut game, roller).expect("bots only make legal moves");
+                Ok(())
+            }
+            Ok(Mover::Program(program)) => program.play_turn(&mut game, roller).map(|_| ()),
+            Err(reason) => Err(reason.clone()),
+        };
+        if turn.is_err() {
+            game.forfeit().expect("the game is not over");
+            // the program is stopped and no longer asked
+            movers[seat] = Err(String::from("forfeited"));
+        }
+    }
+    let side = |seat: usize| {
+        let player = &game.players()[seat];
+        Side {
+            score: player.total_score(),
+            forfeited: player.forfeited,
+        }
+    };
+    [side(seats[0]), side(seats[1])]
+}
+
+fn leaderboard(config: &TournamentConfig, rounds: usize, results: &Results) -> Leaderboard {
+    let standings = results
+        .order(config.bye_points)
+        .into_iter()
+        .enumerate()
+        .map(|(i, entrant)| {
+            let record = &results.records[entrant];
+            let games = record.scores.len() as u64;
+            let won = record.wins as f64 + record.draws as f64 / 2.0;
+            let win_rate = if games == 0 { 0.0 } else { won / games as f64 };
+            Standing {
+                rank: i + 1,
+                entrant,
+                name: config.entrants[entrant].name.clone(),
+                strategy: config.entrants[entrant].strategy.clone(),
+                points: record.points(config.bye_points),
+                games,
+                wins: record.wins,
+                draws: record.draws,
+                losses: record.losses,
+                forfeits: record.forfeits,
+                byes: record.byes,
+                win_rate,
+                win_rate_interval: wilson_interval(won, games),
+                mean: mean(&record.scores),
+                mean_interval: mean_interval(&record.scores),
+            }
+        })
+        .collect();
+    let mut head_to_head = Vec::new();
+    for (a, row) in results.pairs.iter().enumerate() {
+        for (b, [wins, draws, losses]) in row.iter().copied().enumerate() {
+            let games = wins + draws + losses;
+            if games > 0 {
+                head_to_head.push(HeadToHead {
+                    entrant: a,
+                    opponent: b,
+                    games,
+                    wins,
+                    draws,
+                    losses,
+                    win_rate: (wins as f64 + draws as f64 / 2.0) / games as f64,
+                });
+            }
+        }
+    }
+    Leaderboard {
+        format: config.format,
+        rounds,
+        games_per_match: config.games,
+        bye_points: config.bye_points,
+        seed: config.seed,
+        standings,
+        head_to_head,
+    }
+}
+
+fn mean(scores: &[u32]) -> f64 {
+    if scores.is_empty() {
+        return 0.0;
+    }
+    scores.iter().map(|s| f64::from(*s)).sum::<f64>() / scores.len() as f64
+}
+
+/// The normal 95% interval of the mean score.
+fn mean_interval(scores: &[u32]) -> [f64; 2] {
+    let n = scores.len() as f64;
+    let mean = mean(scores);
+    if scores.len() < 2 {
+        return [mean, mean];
+    }
+    let variance = scores.iter().map(|s| (f64::from(*s) - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    let margin = Z * (variance / n).sqrt();
+    [mean - margin, mean + margin]
+}
+
+/// The Wilson 95% interval of a win rate, which stays within 0 to 1 even
+/// for a handful of games or a clean sweep.
+fn wilson_interval(won: f64, games: u64) -> [f64; 2] {
+    if games == 0 {
+        return [0.0, 1.0];
+    }
+    let n = games as f64;
+    let p = won / n;
+    let z2 = Z * Z;
+    let centre = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+    let margin = Z / (1.0 + z2 / n) * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+    [(centre - margin).max(0.0), (centre + margin).min(1.0)]
+}
+
+/// The leaderboard as Markdown.
+impl fmt::Display for Leaderboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# Tournament\n")?;
+        let rounds = match self.format {
+            Format::RoundRobin => String::new(),
+            Format::Swiss => format!(" in {} rounds, a bye worth {} points", self.rounds, self.bye_points),
+        };
+        writeln!(
+            f,
+            "{}{rounds}, {} games a match, seed {}.\n",
+            self.format, self.games_per_match, self.seed
+        )?;
+        writeln!(f, "| Rank | Entrant | Points | Games | W-D-L | Forfeits | Win rate (95% CI) | Mean score (95% CI) |")?;
+        writeln!(f, "|---:|---|---:|---:|---|---:|---|---|")?;
+        for s in &self.standings {
+            let [low, high] = s.win_rate_interval;
+            let [mean_low, mean_high] = s.mean_interval;
+            writeln!(
+                f,
+                "| {} | {} | {} | {} | {}-{}-{} | {} | {:.1}% ({:.1}–{:.1}%) | {:.1} ({:.1}–{:.1}) |",
+                s.rank,
+                s.name,
+                s.points,
+                s.games,
+                s.wins,
+                s.draws,
+                s.losses,
+                s.forfeits,
+                s.win_rate * 100.0,
+                low * 100.0,
+                high * 100.0,
+                s.mean,
+                mean_low,
+                mean_high,
+            )?;
+        }
+
+        writeln!(f, "\n## Head to head\n")?;
+        writeln!(f, "Win rate of each row against each column, draws counting half.\n")?;
+        let names: Vec<&str> = self.standings.iter().map(|s| s.name.as_str()).collect();
+        writeln!(f, "| | {} |", names.join(" | "))?;
+        writeln!(f, "|---|{}", "---:|".repeat(names.len()))?;
+        for entrant in &self.standings {
+            let cells: Vec<String> = self
+                .standings
+                .iter()
+                .map(|opponent| {
+                    self.head_to_head
+                        .iter()
+                        .find(|h| h.entrant == entrant.entrant && h.opponent == opponent.entrant)
+                        .map_or(String::from("–"), |h| format!("{:.0}%", h.win_rate * 100.0))
+                })
+                .collect();
+            writeln!(f, "| {} | {} |", entrant.name, cells.join(" | "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(format: Format, entrants: &[Difficulty], threads: usize) -> TournamentConfig {
+        TournamentConfig {
+            entrants: entrants.iter().map(|d| Entrant::bot(*d)).collect(),
+            format,
+            rounds: 2,
+            games: 6,
+            bye_points: 6.0,
+            seed: 5,
+            threads,
+        }
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let entrants = [Difficulty::Random, Difficulty::Greedy, Difficulty::Heuristic];
+        let leaderboard = run(&config(Format::RoundRobin, &entrants, 1), None);
+        assert_eq!(leaderboard, run(&config(Format::RoundRobin, &entrants, 4), None));
+
+        assert_eq!(leaderboard.rounds, 2);
+        assert!(leaderboard.standings.iter().all(|s| s.games == 12));
+        let points: f64 = leaderboard.standings.iter().map(|s| s.points).sum();
+        assert_eq!(points, 18.0);
+        assert_eq!(leaderboard.standings.last().unwrap().name, "Random");
+        assert_eq!(leaderboard.head_to_head.len(), 6);
+        for s in &leaderboard.standings {
+            assert!(s.win_rate_interval[0] <= s.win_rate && s.win_rate <= s.win_rate_interval[1]);
+            assert!(s.mean_interval[0] <= s.mean && s.mean <= s.mean_interval[1]);
+        }
+        let markdown = leaderboard.to_string();
+        assert!(markdown.contains("| 3 | Random |"));
+    }
+
+    #[test]
+    fn test_swiss_gives_byes_and_avoids_rematches() {
+        let entrants = [Difficulty::Random, Difficulty::Greedy, Difficulty::Heuristic];
+        let leaderboard = run(&config(Format::Swiss, &entrants, 2), None);
+        assert!(leaderboard.standings.iter().all(|s| s.byes <= 1));
+        assert_eq!(leaderboard.standings.iter().map(|s| s.byes).sum::<u64>(), 2);
+        // no pair meets twice in two rounds of three entrants
+        assert!(leaderboard.head_to_head.iter().all(|h| h.games == 6));
+        let points: f64 = leaderboard.standings.iter().map(|s| s.points).sum();
+        assert_eq!(points, 12.0 + 2.0 * 6.0);
+        assert!(leaderboard.to_string().contains("Swiss in 2 rounds, a bye worth 6 points, 6 games a match"));
+
+        let mut unrewarded = config(Format::Swiss, &entrants, 2);
+        unrewarded.bye_points = 0.0;
+        let points: f64 = run(&unrewarded, None).standings.iter().map(|s| s.points).sum();
+        assert_eq!(points, 12.0);
+    }
+
+    #[test]
+    fn test_head_to_head_tells_namesakes_apart() {
+        let leaderboard = run(&config(Format::RoundRobin, &[Difficulty::Greedy; 2], 1), None);
+        assert_eq!(leaderboard.head_to_head.len(), 2);
+        // the same bot on the same dice draws every game
+        let markdown = leaderboard.to_string();
+        assert!(markdown.contains("| Greedy | – | 50% |"));
+        assert!(markdown.contains("| Greedy | 50% | – |"));
+    }
+
+    #[test]
+    fn test_wilson_interval() {
+        let [low, high] = wilson_interval(10.0, 10);
+        assert!(low > 0.69 && low < 0.73);
+        assert_eq!(high, 1.0);
+        assert_eq!(wilson_interval(0.0, 0), [0.0, 1.0]);
+    }
+}